@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+use std::fs::{canonicalize, create_dir_all, metadata, remove_file, rename, File};
+use std::io::{BufReader, BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use glib::{debug, warn};
+use itertools::Itertools;
+use rayon::prelude::*;
+use resolve_path::PathResolveExt;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::ide::data::IDEData;
+use crate::ide::properties::properties_env_vars;
+use crate::recent_project::path_macros::PATH_MACROS_FILE_PATH;
+use crate::recent_project::{mount_point, run_with_timeout, RecentProject, PROJECT_SETTINGS_DIR};
+use crate::G_LOG_DOMAIN;
+
+static CACHE_DIR_NAME: &str = "rofi-jetbrains";
+static CACHE_FILE_NAME: &str = "index.json";
+static CACHE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct IndexCache {
+  version: String,
+  key: CacheKey,
+  discovery_sources: Vec<PathBuf>,
+  ides: Option<CachedValue<Vec<IDEData>>>,
+  projects: HashMap<PathBuf, CachedValue<Vec<RecentProject>>>,
+  #[serde(skip)]
+  enabled: bool,
+  #[serde(skip)]
+  probe_timeout: Duration,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+struct CacheKey {
+  show_missing_projects: bool,
+  unprobed_paths: Vec<PathBuf>,
+  properties_env_vars: Vec<(String, PathBuf)>,
+}
+
+impl CacheKey {
+  fn new(config: &Config) -> Self {
+    Self {
+      show_missing_projects: config.show_missing_projects,
+      unprobed_paths: config.unprobed_paths.clone(),
+      properties_env_vars: properties_env_vars(),
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct CachedValue<T> {
+  sources: Vec<SourceStamp>,
+  #[serde(default)]
+  probed_sources: Vec<SourceStamp>,
+  value: T,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct SourceStamp {
+  path: PathBuf,
+  target: Option<PathBuf>,
+  modified: Option<SystemTime>,
+}
+
+impl SourceStamp {
+  fn new<T: AsRef<Path>>(path: T) -> Self {
+    let path = path.as_ref();
+
+    // Nix store paths share the same mtime, so switching a profile only changes where the symlinks point
+    Self {
+      path: path.to_path_buf(),
+      target: canonicalize(path).ok(),
+      modified: metadata(path).and_then(|meta| meta.modified()).ok(),
+    }
+  }
+
+  fn is_fresh(&self) -> bool {
+    *self == Self::new(&self.path)
+  }
+}
+
+impl<T> CachedValue<T> {
+  fn new<I: IntoIterator<Item = PathBuf>>(sources: I, value: T) -> Self {
+    Self {
+      sources: sources.into_iter().map(SourceStamp::new).collect(),
+      probed_sources: vec![],
+      value,
+    }
+  }
+
+  fn get(&self, probe_timeout: Duration) -> Option<&T> {
+    let is_fresh = self.sources.par_iter().all(SourceStamp::is_fresh)
+      && (self.probed_sources.is_empty()
+        || probed_stamps(
+          self.probed_sources.iter().map(|stamp| stamp.path.clone()),
+          probe_timeout,
+        )
+        .is_some_and(|stamps| stamps == self.probed_sources));
+
+    is_fresh.then_some(&self.value)
+  }
+}
+
+impl IndexCache {
+  pub fn load(config: &Config) -> Self {
+    if config.clear_cache {
+      Self::clear();
+    }
+
+    if config.disable_cache {
+      debug!("Index cache is disabled, skipping...");
+      return Self::default();
+    }
+
+    let path = cache_file_path();
+    let key = CacheKey::new(config);
+    debug!("Loading index cache from {:?}...", &path);
+
+    let cache = File::open(&path)
+      .map_err(|err| {
+        if err.kind() != ErrorKind::NotFound {
+          warn!("Failed to read {:?} file, reason: {}", &path, err)
+        }
+      })
+      .ok()
+      .and_then(|file| {
+        serde_json::from_reader::<_, Self>(BufReader::new(file))
+          .map_err(|err| warn!("Failed to parse {:?} file, reason: {}", &path, err))
+          .ok()
+      })
+      .filter(|cache| {
+        // Entries written by a different version of the plugin might have a different meaning
+        cache.version == CACHE_VERSION
+      })
+      .filter(|cache| {
+        // Settings affecting which IDEs and projects are found invalidate the whole cache
+        cache.key == key
+      })
+      .unwrap_or_default();
+
+    Self {
+      version: CACHE_VERSION.to_owned(),
+      key,
+      enabled: true,
+      probe_timeout: config.probe_timeout,
+      ..cache
+    }
+  }
+
+  pub fn clear() {
+    let path = cache_file_path();
+    debug!("Clearing index cache at {:?}...", &path);
+
+    if let Err(err) = remove_file(&path) {
+      if err.kind() != ErrorKind::NotFound {
+        warn!("Failed to remove {:?} file, reason: {}", &path, err);
+      }
+    }
+  }
+
+  pub fn save(&self) {
+    if !self.enabled {
+      return;
+    }
+
+    let path = cache_file_path();
+    let temp_path = path.with_extension(format!("json.{}.tmp", process::id()));
+    debug!("Saving index cache to {:?}...", &path);
+
+    // Write into a temporary file first, so a concurrent instance never reads a partial cache
+    let result = path
+      .parent()
+      .map_or(Ok(()), create_dir_all)
+      .and_then(|_| File::create(&temp_path))
+      .and_then(|file| {
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer(&mut writer, self)?;
+        writer.flush()
+      })
+      .and_then(|_| rename(&temp_path, &path));
+
+    if let Err(err) = result {
+      let _ = remove_file(&temp_path);
+      warn!("Failed to write {:?} file, reason: {}", &path, err);
+    }
+  }
+
+  pub fn ides(&self, discovery_sources: &[PathBuf]) -> Option<Vec<Arc<IDEData>>> {
+    // Configured install directories might have changed, invalidating the whole discovery
+    if !self.enabled || self.discovery_sources != discovery_sources {
+      return None;
+    }
+
+    let ides = self.ides.as_ref()?.get(self.probe_timeout)?;
+    Some(ides.iter().cloned().map(Arc::new).collect())
+  }
+
+  pub fn set_ides(&mut self, discovery_sources: Vec<PathBuf>, ides: &[Arc<IDEData>]) {
+    if !self.enabled {
+      return;
+    }
+
+    let sources = discovery_sources
+      .iter()
+      .cloned()
+      .chain(ides.iter().flat_map(|ide| ide_sources(ide)))
+      .chain(
+        self
+          .key
+          .properties_env_vars
+          .iter()
+          .map(|(_, path)| path.clone()),
+      )
+      .collect::<Vec<_>>();
+    let ides = ides.iter().map(|ide| IDEData::clone(ide)).collect();
+
+    self.discovery_sources = discovery_sources;
+    self.ides = Some(CachedValue::new(sources, ides));
+  }
+
+  pub fn projects<T: AsRef<Path>>(
+    &self,
+    xml_path: T,
+    ide: &Arc<IDEData>,
+  ) -> Option<Vec<RecentProject>> {
+    let projects = self
+      .projects
+      .get(xml_path.as_ref())?
+      .get(self.probe_timeout)?;
+
+    // Cached projects hold a copy of the IDE, so point them back to the discovered one
+    Some(
+      projects
+        .iter()
+        .cloned()
+        .map(|mut project| {
+          project.ide = Arc::clone(ide);
+          for origin in &mut project.origins {
+            origin.ide = Arc::clone(ide);
+          }
+          project
+        })
+        .collect(),
+    )
+  }
+
+  pub fn set_projects(&mut self, projects: Vec<(PathBuf, Vec<RecentProject>)>) {
+    if !self.enabled {
+      return;
+    }
+
+    // Replace all entries, so files that no longer exist are dropped from the cache
+    self.projects = projects
+      .into_iter()
+      .filter_map(|(xml_path, projects)| {
+        // Paths of the projects also depend on the path macros defined in the same config
+        let path_macros_path = xml_path
+          .parent()
+          .and_then(Path::parent)
+          .map(|config_path| config_path.join(PATH_MACROS_FILE_PATH))
+          .unwrap_or_default();
+        // Projects also hold the results of probing their paths, which change independently of the XML
+        let probed_paths = projects
+          .iter()
+          .filter(|project| !self.is_unprobed(&project.path))
+          .flat_map(|project| {
+            [
+              project.path.clone(),
+              project.working_dir().join(PROJECT_SETTINGS_DIR),
+            ]
+          })
+          .collect::<Vec<_>>();
+
+        // Files with projects on unresponsive mounts aren't cached, they can't be validated anyway
+        let probed_sources = probed_stamps(probed_paths, self.probe_timeout)?;
+
+        let mut value = CachedValue::new([xml_path.clone(), path_macros_path], projects);
+        value.probed_sources = probed_sources;
+        Some((xml_path, value))
+      })
+      .collect();
+  }
+
+  fn is_unprobed(&self, path: &Path) -> bool {
+    // Excluded paths might sit on slow mounts, so they shouldn't be touched when validating the cache
+    self
+      .key
+      .unprobed_paths
+      .iter()
+      .any(|prefix| path.starts_with(prefix))
+  }
+}
+
+fn probed_stamps<I>(paths: I, timeout: Duration) -> Option<Vec<SourceStamp>>
+where
+  I: IntoIterator<Item = PathBuf>,
+{
+  // Project paths might sit on unresponsive mounts, so they are accessed with a timeout, once per mount
+  let stamps = paths
+    .into_iter()
+    .into_group_map_by(|path| mount_point(path))
+    .into_values()
+    .collect::<Vec<_>>()
+    .into_par_iter()
+    .map(|paths| {
+      let path = paths.first()?.clone();
+      run_with_timeout(&path, timeout, move || {
+        paths.iter().map(SourceStamp::new).collect::<Vec<_>>()
+      })
+    })
+    .collect::<Option<Vec<_>>>()?;
+
+  Some(stamps.into_iter().flatten().sorted().collect())
+}
+
+fn cache_file_path() -> PathBuf {
+  dirs::cache_dir()
+    .unwrap_or_else(|| "~/.cache".resolve().to_path_buf())
+    .join(CACHE_DIR_NAME)
+    .join(CACHE_FILE_NAME)
+}
+
+fn ide_sources(ide: &IDEData) -> Vec<PathBuf> {
+  vec![
+    ide.install_dir.join("product-info.json"),
+    ide.install_dir.join("build.txt"),
+    ide.install_dir.join("bin/idea.properties"),
+    ide.config_path.join("idea.properties"),
+  ]
+}
@@ -1,29 +1,42 @@
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
+use std::time::Duration;
 
 use glib::warn;
 use itertools::Itertools;
 use resolve_path::PathResolveExt;
 
-use crate::ide::IDEType;
+use crate::ide::version::IDEVersionPreference;
+use crate::ide::IDEFilter;
 use crate::macros::ensure_result;
 use crate::rofi::xrmoptions::config_parse_option;
 use crate::G_LOG_DOMAIN;
 
 static ROFI_CONFIG_PREFIX: &str = "jetbrains-";
+static DEFAULT_PROBE_TIMEOUT_MS: u32 = 1000;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Config {
-  pub install_dir: PathBuf,
-  pub custom_aliases: Vec<(String, IDEType)>,
+  pub install_dirs: Vec<PathBuf>,
+  pub custom_aliases: Vec<(String, IDEFilter)>,
+  pub version_preferences: Vec<(IDEFilter, IDEVersionPreference)>,
   pub use_clion_devshell: bool,
+  pub disable_cache: bool,
+  pub clear_cache: bool,
+  pub probe_timeout: Duration,
+  pub unprobed_paths: Vec<PathBuf>,
+  pub show_missing_projects: bool,
 }
 
 impl Config {
   pub fn from_rofi() -> Self {
-    let install_dir: Option<PathBuf> = config_parse_option(
+    let install_dirs = config_parse_option::<Option<Vec<PathBuf>>>(
       &(ROFI_CONFIG_PREFIX.to_owned() + "install-dir"),
-      "A path to the directory where all IDEs are installed",
-    );
+      "A rofi list of directories where IDEs are installed",
+    )
+    .unwrap_or_default()
+    .into_iter()
+    .map(|path| path.resolve().to_path_buf())
+    .collect::<Vec<_>>();
 
     let custom_aliases = config_parse_option::<Option<Vec<String>>>(
       &(ROFI_CONFIG_PREFIX.to_owned() + "custom-aliases"),
@@ -31,11 +44,49 @@ impl Config {
     )
     .unwrap_or_default();
 
+    let version_preferences = config_parse_option::<Option<Vec<String>>>(
+      &(ROFI_CONFIG_PREFIX.to_owned() + "version-preference"),
+      "A rofi list declaring which IDE version should be used to open projects",
+    )
+    .unwrap_or_default();
+
     let use_clion_devshell = config_parse_option::<bool>(
       &(ROFI_CONFIG_PREFIX.to_owned() + "use-clion-devshell"),
       "Whether to use the nix devshell when opening a CLion project",
     );
 
+    let disable_cache = config_parse_option::<bool>(
+      &(ROFI_CONFIG_PREFIX.to_owned() + "disable-cache"),
+      "Whether to disable the on-disk cache of discovered IDEs and recent projects",
+    );
+
+    let clear_cache = config_parse_option::<bool>(
+      &(ROFI_CONFIG_PREFIX.to_owned() + "clear-cache"),
+      "Whether to clear the on-disk cache of discovered IDEs and recent projects on startup",
+    );
+
+    let probe_timeout = match config_parse_option::<u32>(
+      &(ROFI_CONFIG_PREFIX.to_owned() + "probe-timeout"),
+      "Time in milliseconds after which a project path is considered unavailable",
+    ) {
+      0 => DEFAULT_PROBE_TIMEOUT_MS,
+      timeout => timeout,
+    };
+
+    let unprobed_paths = config_parse_option::<Option<Vec<PathBuf>>>(
+      &(ROFI_CONFIG_PREFIX.to_owned() + "unprobed-paths"),
+      "A rofi list of path prefixes (e.g. network mounts) where projects are never accessed",
+    )
+    .unwrap_or_default()
+    .into_iter()
+    .map(|path| path.resolve().to_path_buf())
+    .collect::<Vec<_>>();
+
+    let show_missing_projects = config_parse_option::<bool>(
+      &(ROFI_CONFIG_PREFIX.to_owned() + "show-missing-projects"),
+      "Whether to list projects whose path no longer exists",
+    );
+
     let custom_aliases = custom_aliases
       .into_iter()
       .map(|raw| -> Result<_, _> {
@@ -45,14 +96,41 @@ impl Config {
           raw
         );
 
-        let ide_type = ensure_result!(
-          IDEType::from_product_code(product_code),
-          "Failed to parse {:?} alias, {:?} is an unknown IDE",
+        let filter = ensure_result!(
+          IDEFilter::from_any_product_code(product_code),
+          "Failed to parse {:?} alias, {:?} is not a valid product code",
           alias,
           product_code
         );
 
-        Ok((alias.to_owned(), ide_type))
+        Ok((alias.to_owned(), filter))
+      })
+      .filter_map(|res| match res {
+        Ok(v) => Some(v),
+        Err(err) => {
+          warn!("{}", err);
+          None
+        }
+      })
+      .collect::<Vec<_>>();
+
+    let version_preferences = version_preferences
+      .into_iter()
+      .map(|raw| -> Result<_, _> {
+        let (product_code, preference) = ensure_result!(
+          raw.split_once(':'),
+          "Failed to parse version preference, {:?} is not a valid preference",
+          raw
+        );
+
+        let filter = ensure_result!(
+          IDEFilter::from_any_product_code(product_code),
+          "Failed to parse {:?} version preference, {:?} is not a valid product code",
+          preference,
+          product_code
+        );
+
+        Ok((filter, IDEVersionPreference::parse(preference)))
       })
       .filter_map(|res| match res {
         Ok(v) => Some(v),
@@ -64,13 +142,15 @@ impl Config {
       .collect::<Vec<_>>();
 
     Self {
-      install_dir: install_dir.unwrap_or_else(|| {
-        Path::new("~/.local/share/JetBrains/Toolbox/apps/")
-          .resolve()
-          .to_path_buf()
-      }),
+      install_dirs,
       custom_aliases,
+      version_preferences,
       use_clion_devshell,
+      disable_cache,
+      clear_cache,
+      probe_timeout: Duration::from_millis(probe_timeout as u64),
+      unprobed_paths,
+      show_missing_projects,
     }
   }
 }
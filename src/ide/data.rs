@@ -1,41 +1,143 @@
+use std::env;
 use std::path::{Path, PathBuf};
 
+use glib::warn;
+use serde::{Deserialize, Serialize};
+
 use crate::ide::product_info::IDEProductInfo;
-use crate::ide::IDEType;
+use crate::ide::version::IDEBuildNumber;
+use crate::ide::{IDEChannel, IDEEdition, IDEType};
+use crate::G_LOG_DOMAIN;
 
 #[derive(Debug, Clone)]
+pub struct IDEPaths {
+  pub config_path: PathBuf,
+  pub system_path: PathBuf,
+  pub plugins_path: PathBuf,
+  pub log_path: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct IDEData {
+  pub name: String,
   pub ide_type: IDEType,
+  pub product_code: String,
+  pub edition: Option<IDEEdition>,
   pub version: String,
+  pub build_number: IDEBuildNumber,
+  pub channel: IDEChannel,
+  pub custom_name: Option<String>,
+  pub install_dir: PathBuf,
+  pub install_root: PathBuf,
   pub config_path: PathBuf,
+  pub system_path: PathBuf,
+  pub plugins_path: PathBuf,
+  pub log_path: PathBuf,
   pub fallback_icon_path: PathBuf,
   pub icon_name: String,
+  pub desktop_icon: Option<String>,
   pub launcher_path: PathBuf,
+  pub launcher_args: Vec<String>,
+  pub java_executable_path: Option<PathBuf>,
+  pub vm_options_path: Option<PathBuf>,
+  pub additional_jvm_arguments: Vec<String>,
+  pub flatpak_id: Option<String>,
 }
 
 impl IDEData {
   pub fn from_product_info<A, B>(
     product_info: &IDEProductInfo,
     install_dir: A,
-    config_path: B,
-  ) -> Self
+    install_root: B,
+    paths: IDEPaths,
+  ) -> Result<Self, ()>
   where
     A: AsRef<Path>,
     B: AsRef<Path>,
   {
-    let config_path = config_path.as_ref();
     let install_dir = install_dir.as_ref();
 
-    let launch_settings = &product_info.launch_settings[0];
+    let Some(launch_settings) = product_info.current_launch_settings() else {
+      warn!(
+        "Ignoring {} in {:?}, no launch entry matches the current platform ({}/{})",
+        &product_info.name,
+        install_dir,
+        env::consts::OS,
+        env::consts::ARCH
+      );
+      return Err(());
+    };
+    let product = product_info.product();
+    let launcher_path = install_dir.join(&launch_settings.launcher_path);
+    let icon_name = launch_settings.startup_wm_class.clone().unwrap_or_else(|| {
+      let launcher_name = launcher_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+      format!("jetbrains-{launcher_name}")
+    });
 
-    Self {
-      config_path: config_path.to_path_buf(),
-      ide_type: product_info.ide_type.clone(),
+    Ok(Self {
+      config_path: paths.config_path,
+      system_path: paths.system_path,
+      plugins_path: paths.plugins_path,
+      log_path: paths.log_path,
+      name: product_info.name.clone(),
+      edition: product.edition(),
+      ide_type: product.ide_type,
+      product_code: product.code,
       version: product_info.version.clone(),
+      build_number: IDEBuildNumber::parse(&product_info.build_number),
+      // Builds outside of Toolbox only tell their channel by the version suffix (e.g. "EAP")
+      channel: product_info
+        .version_suffix
+        .as_ref()
+        .and_then(|suffix| suffix.split_whitespace().next())
+        .and_then(IDEChannel::from_name)
+        .unwrap_or_default(),
+      custom_name: None,
+      install_dir: install_dir.to_path_buf(),
+      install_root: install_root.as_ref().to_path_buf(),
       fallback_icon_path: install_dir.join(&product_info.svg_icon_path),
-      icon_name: launch_settings.startup_wm_class.clone(),
-      launcher_path: install_dir.join(&launch_settings.launcher_path),
+      icon_name,
+      desktop_icon: None,
+      launcher_path,
+      launcher_args: vec![],
+      java_executable_path: launch_settings
+        .java_executable_path
+        .as_ref()
+        .map(|path| install_dir.join(path)),
+      vm_options_path: launch_settings
+        .vm_options_file_path
+        .as_ref()
+        .map(|path| install_dir.join(path)),
+      additional_jvm_arguments: launch_settings.additional_jvm_arguments.clone(),
+      flatpak_id: None,
+    })
+  }
+
+  pub fn display_name(&self) -> String {
+    if let Some(custom_name) = &self.custom_name {
+      return custom_name.clone();
+    }
+
+    match &self.edition {
+      Some(edition) if !self.name.contains(&edition.to_string()) => {
+        format!("{} {}", self.name, edition)
+      }
+      _ => self.name.clone(),
+    }
+  }
+
+  pub fn launch_command(&self) -> Vec<String> {
+    match &self.flatpak_id {
+      Some(flatpak_id) => vec!["flatpak".to_string(), "run".to_string(), flatpak_id.clone()],
+      None => [self.launcher_path.to_string_lossy().into_owned()]
+        .into_iter()
+        .chain(self.launcher_args.iter().cloned())
+        .collect(),
     }
   }
 }
@@ -0,0 +1,168 @@
+use std::env;
+use std::fs::{read_dir, read_to_string};
+use std::path::{Path, PathBuf};
+
+use glib::debug;
+use itertools::Itertools;
+use rayon::prelude::*;
+
+use crate::ide::data::IDEData;
+use crate::ide::discovery::{
+  find_product_info_near, find_program, ide_from_product_info, BaseDirs,
+};
+use crate::G_LOG_DOMAIN;
+
+static DESKTOP_ENTRY_PREFIX: &str = "jetbrains-";
+static DESKTOP_ENTRY_EXTENSION: &str = ".desktop";
+static DESKTOP_ENTRY_GROUP: &str = "[Desktop Entry]";
+static DEFAULT_XDG_DATA_DIRS: &str = "/usr/local/share/:/usr/share/";
+
+#[derive(Debug, Clone, Default)]
+pub struct DesktopEntry {
+  pub path: PathBuf,
+  pub exec: Vec<String>,
+  pub icon: Option<String>,
+  pub startup_wm_class: Option<String>,
+}
+
+impl DesktopEntry {
+  pub fn from_file<T: AsRef<Path>>(path: T) -> Option<Self> {
+    let path = path.as_ref();
+    let content = read_to_string(path).ok()?;
+    let mut entry = DesktopEntry {
+      path: path.to_path_buf(),
+      ..Default::default()
+    };
+
+    let mut in_main_group = false;
+    for line in content.lines().map(str::trim) {
+      if line.starts_with('[') {
+        in_main_group = line == DESKTOP_ENTRY_GROUP;
+        continue;
+      }
+      if !in_main_group || line.starts_with('#') {
+        continue;
+      }
+
+      let Some((key, value)) = line.split_once('=') else {
+        continue;
+      };
+
+      match key.trim() {
+        "Exec" => entry.exec = parse_exec(value.trim()),
+        "Icon" => entry.icon = Some(value.trim().to_owned()),
+        "StartupWMClass" => entry.startup_wm_class = Some(value.trim().to_owned()),
+        _ => {}
+      }
+    }
+
+    if entry.exec.is_empty() {
+      debug!("Ignoring {:?} desktop entry, it has no Exec key", path);
+      return None;
+    }
+
+    Some(entry)
+  }
+}
+
+pub fn find_desktop_entries(base_dirs: &BaseDirs) -> Vec<DesktopEntry> {
+  desktop_entry_dirs(base_dirs)
+    .into_iter()
+    .flat_map(|dir| {
+      debug!("Looking for desktop entries in {:?}...", &dir);
+      read_dir(&dir).into_iter().flatten().flatten()
+    })
+    .map(|entry| entry.path())
+    .filter(|path| {
+      path.file_name().is_some_and(|name| {
+        let name = name.to_string_lossy();
+        name.starts_with(DESKTOP_ENTRY_PREFIX) && name.ends_with(DESKTOP_ENTRY_EXTENSION)
+      })
+    })
+    .collect::<Vec<_>>()
+    .into_par_iter()
+    .filter_map(DesktopEntry::from_file)
+    .collect()
+}
+
+pub fn sources(base_dirs: &BaseDirs) -> Vec<PathBuf> {
+  desktop_entry_dirs(base_dirs)
+}
+
+pub fn discover(entries: &[DesktopEntry], base_dirs: &BaseDirs) -> Vec<IDEData> {
+  entries
+    .par_iter()
+    .filter_map(|entry| {
+      debug!("Resolving IDE from {:?} desktop entry...", &entry.path);
+      let product_info_path = find_product_info_near(find_program(&entry.exec[0])?)?;
+      let install_root = entry.path.parent()?;
+      let mut ide = ide_from_product_info(product_info_path, install_root, base_dirs)?;
+
+      debug!("Using {:?} as launcher", &entry.exec);
+      ide.launcher_path = PathBuf::from(&entry.exec[0]);
+      ide.launcher_args = entry.exec[1..].to_vec();
+      if let Some(startup_wm_class) = &entry.startup_wm_class {
+        ide.icon_name = startup_wm_class.clone();
+      }
+
+      Some(ide)
+    })
+    .collect()
+}
+
+pub fn apply_desktop_icons(ides: &mut [IDEData], entries: &[DesktopEntry]) {
+  for ide in ides {
+    if let Some(icon) = entries
+      .iter()
+      .find(|entry| entry.startup_wm_class.as_ref() == Some(&ide.icon_name))
+      .and_then(|entry| entry.icon.clone())
+    {
+      debug!("Using {:?} desktop icon for {}", &icon, ide.display_name());
+      ide.desktop_icon = Some(icon);
+    }
+  }
+}
+
+fn desktop_entry_dirs(base_dirs: &BaseDirs) -> Vec<PathBuf> {
+  let data_dirs = env::var("XDG_DATA_DIRS")
+    .ok()
+    .filter(|dirs| !dirs.is_empty())
+    .unwrap_or_else(|| DEFAULT_XDG_DATA_DIRS.to_owned());
+
+  [base_dirs.data_home.clone()]
+    .into_iter()
+    .chain(env::split_paths(&data_dirs))
+    .map(|dir| dir.join("applications"))
+    .unique()
+    .collect()
+}
+
+fn parse_exec(raw: &str) -> Vec<String> {
+  let mut args = vec![];
+  let mut current = String::new();
+  let mut in_quotes = false;
+  let mut chars = raw.chars();
+
+  while let Some(c) = chars.next() {
+    match c {
+      '"' => in_quotes = !in_quotes,
+      '\\' if in_quotes => current.extend(chars.next()),
+      c if c.is_whitespace() && !in_quotes => {
+        if !current.is_empty() {
+          args.push(std::mem::take(&mut current));
+        }
+      }
+      c => current.push(c),
+    }
+  }
+  if !current.is_empty() {
+    args.push(current);
+  }
+
+  // Field codes (e.g. "%f", "%U") are substituted by the launcher, so they are dropped here
+  args
+    .into_iter()
+    .filter(|arg| !(arg.len() == 2 && arg.starts_with('%') && arg != "%%"))
+    .map(|arg| arg.replace("%%", "%"))
+    .collect()
+}
@@ -0,0 +1,106 @@
+use std::fs::read_dir;
+use std::path::{Path, PathBuf};
+
+use glib::debug;
+use rayon::prelude::*;
+use resolve_path::PathResolveExt;
+use wax::{Glob, LinkBehavior, WalkBehavior, WalkEntry};
+
+use crate::ide::data::IDEData;
+use crate::ide::discovery::{ide_from_product_info, BaseDirs};
+use crate::traits::MapToErrorLog;
+use crate::G_LOG_DOMAIN;
+
+static FLATPAK_SYSTEM_INSTALL_DIR: &str = "/var/lib/flatpak/app/";
+static FLATPAK_USER_INSTALL_DIR: &str = "flatpak/app/";
+static FLATPAK_APP_PREFIXES: [&str; 2] = ["com.jetbrains.", "com.google.AndroidStudio"];
+static FLATPAK_PRODUCT_INFO_GLOB_PATTERN: &str = "**/product-info.json";
+static FLATPAK_PRODUCT_INFO_MAX_DEPTH: usize = 3;
+
+pub fn discover(base_dirs: &BaseDirs) -> Vec<IDEData> {
+  let Ok(glob) = Glob::new(FLATPAK_PRODUCT_INFO_GLOB_PATTERN)
+    .map_to_error_log("Failed to set up glob matcher for Flatpak IDE product info")
+  else {
+    return vec![];
+  };
+
+  install_roots(base_dirs)
+    .into_iter()
+    .flat_map(|install_root| {
+      find_flatpak_apps(&install_root)
+        .into_iter()
+        .map(move |(app_id, app_dir)| (install_root.clone(), app_id, app_dir))
+    })
+    .collect::<Vec<_>>()
+    .into_par_iter()
+    .filter_map(|(install_root, app_id, app_dir)| {
+      let files_dir = app_dir.join("current/active/files");
+      debug!(
+        "Looking for Flatpak IDE product info in {:?}...",
+        &files_dir
+      );
+
+      let product_info_path = glob
+        .walk_with_behavior(
+          &files_dir,
+          WalkBehavior {
+            depth: FLATPAK_PRODUCT_INFO_MAX_DEPTH,
+            link: LinkBehavior::ReadTarget,
+          },
+        )
+        .flatten()
+        .map(WalkEntry::into_path)
+        .next()?;
+
+      // Flatpak apps have their XDG base directories redirected into the sandbox
+      let app_home = Path::new("~/.var/app/")
+        .join(&app_id)
+        .resolve()
+        .to_path_buf();
+      let base_dirs = BaseDirs {
+        home: base_dirs.home.clone(),
+        config_home: app_home.join("config"),
+        cache_home: app_home.join("cache"),
+        data_home: app_home.join("data"),
+      };
+      let mut ide = ide_from_product_info(product_info_path, install_root, &base_dirs)?;
+
+      debug!("Using Flatpak app {:?} as launcher", &app_id);
+      ide.flatpak_id = Some(app_id);
+      Some(ide)
+    })
+    .collect()
+}
+
+pub fn sources(base_dirs: &BaseDirs) -> Vec<PathBuf> {
+  install_roots(base_dirs)
+}
+
+fn install_roots(base_dirs: &BaseDirs) -> Vec<PathBuf> {
+  vec![
+    PathBuf::from(FLATPAK_SYSTEM_INSTALL_DIR),
+    base_dirs.data_home.join(FLATPAK_USER_INSTALL_DIR),
+  ]
+}
+
+fn find_flatpak_apps(install_root: &Path) -> Vec<(String, PathBuf)> {
+  let Ok(entries) = read_dir(install_root) else {
+    debug!(
+      "Skipping Flatpak install root {:?}, the directory doesn't exist",
+      install_root
+    );
+    return vec![];
+  };
+
+  entries
+    .flatten()
+    .filter_map(|entry| {
+      let app_id = entry.file_name().to_string_lossy().to_string();
+
+      FLATPAK_APP_PREFIXES
+        .iter()
+        .any(|prefix| app_id.starts_with(prefix))
+        .then(|| (app_id, entry.path()))
+    })
+    .collect()
+}
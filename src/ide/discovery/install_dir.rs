@@ -0,0 +1,86 @@
+use std::fs::canonicalize;
+use std::path::{Path, PathBuf};
+
+use glib::debug;
+use itertools::Itertools;
+use rayon::prelude::*;
+use wax::{Glob, LinkBehavior, WalkEntry};
+
+use crate::config::Config;
+use crate::ide::data::IDEData;
+use crate::ide::discovery::legacy::ide_from_build_file;
+use crate::ide::discovery::{ide_from_product_info, BaseDirs};
+use crate::traits::MapToErrorLog;
+use crate::G_LOG_DOMAIN;
+
+static PRODUCT_INFO_FILE_NAME: &str = "product-info.json";
+static LEGACY_BUILD_FILE_NAME: &str = "build.txt";
+static PRODUCT_INFO_GLOB_PATTERN: &str = "*/{product-info.json,build.txt}";
+
+static TOOLBOX_APPS_DIR: &str = "JetBrains/Toolbox/apps/";
+static WELL_KNOWN_INSTALL_DIRS: [&str; 3] = ["/opt/", "/usr/share/", "/usr/local/share/"];
+
+pub fn discover(config: &Config, base_dirs: &BaseDirs) -> Vec<IDEData> {
+  let Ok(glob) = Glob::new(PRODUCT_INFO_GLOB_PATTERN)
+    .map_to_error_log("Failed to set up glob matcher for IDE product info")
+  else {
+    return vec![];
+  };
+
+  install_roots(config, base_dirs)
+    .par_iter()
+    .flat_map(|root| {
+      find_product_info_files(&glob, root)
+        .into_par_iter()
+        .filter_map(move |entry| {
+          if entry.ends_with(PRODUCT_INFO_FILE_NAME) {
+            ide_from_product_info(entry, root, base_dirs)
+          } else if entry.ends_with(LEGACY_BUILD_FILE_NAME)
+            && !entry.with_file_name(PRODUCT_INFO_FILE_NAME).exists()
+          {
+            // Older IDEs only ship the build file, so use it as a fallback
+            ide_from_build_file(entry, root, base_dirs)
+          } else {
+            None
+          }
+        })
+    })
+    .collect()
+}
+
+pub fn sources(config: &Config, base_dirs: &BaseDirs) -> Vec<PathBuf> {
+  install_roots(config, base_dirs)
+}
+
+fn install_roots(config: &Config, base_dirs: &BaseDirs) -> Vec<PathBuf> {
+  config
+    .install_dirs
+    .iter()
+    .cloned()
+    .chain([base_dirs.data_home.join(TOOLBOX_APPS_DIR)])
+    .chain(WELL_KNOWN_INSTALL_DIRS.iter().map(PathBuf::from))
+    .unique_by(|root| canonicalize(root).unwrap_or_else(|_| root.clone()))
+    .collect()
+}
+
+fn find_product_info_files(glob: &Glob, root: &Path) -> Vec<PathBuf> {
+  if !root.is_dir() {
+    debug!(
+      "Skipping install root {:?}, the directory doesn't exist",
+      root
+    );
+    return vec![];
+  }
+
+  debug!("Searching for installed IDEs in {:?}...", root);
+  glob
+    .walk_with_behavior(root, LinkBehavior::ReadTarget)
+    .filter_map(|entry| match entry {
+      Ok(entry) => Some(WalkEntry::into_path(entry)),
+      Err(err) => {
+        debug!("Failed to walk install root {:?}, reason: {}", root, err);
+        None
+      }
+    })
+    .collect()
+}
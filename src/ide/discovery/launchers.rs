@@ -0,0 +1,165 @@
+use std::env;
+use std::fs::{canonicalize, read_to_string};
+use std::path::{Component, Path, PathBuf};
+
+use glib::debug;
+use itertools::Itertools;
+use rayon::prelude::*;
+use resolve_path::PathResolveExt;
+use wax::{Glob, LinkBehavior, WalkBehavior, WalkEntry};
+
+use crate::ide::data::IDEData;
+use crate::ide::discovery::{
+  find_product_info_near, ide_from_product_info, BaseDirs, PRODUCT_INFO_MAX_DEPTH,
+};
+use crate::traits::MapToErrorLog;
+use crate::G_LOG_DOMAIN;
+
+static NIX_STORE_DIR: &str = "/nix/store/";
+static NIX_PROFILE_DIRS: [&str; 3] = [
+  "~/.nix-profile/bin/",
+  "/etc/profiles/per-user/$USER/bin/",
+  "/run/current-system/sw/bin/",
+];
+static NIX_PRODUCT_INFO_GLOB_PATTERN: &str = "**/product-info.json";
+
+// Launcher names used by nixpkgs and by install scripts linking IDEs into "$PATH"
+static KNOWN_LAUNCHERS: [&str; 27] = [
+  "idea",
+  "idea-ultimate",
+  "idea-community",
+  "pycharm",
+  "pycharm-professional",
+  "pycharm-community",
+  "clion",
+  "webstorm",
+  "phpstorm",
+  "goland",
+  "rubymine",
+  "ruby-mine",
+  "datagrip",
+  "dataspell",
+  "rider",
+  "rustrover",
+  "rust-rover",
+  "aqua",
+  "mps",
+  "writerside",
+  "gateway",
+  "android-studio",
+  "android-studio-beta",
+  "android-studio-canary",
+  "studio",
+  "fleet",
+  "jetbrains-client",
+];
+
+pub fn discover(base_dirs: &BaseDirs) -> Vec<IDEData> {
+  let Ok(glob) = Glob::new(NIX_PRODUCT_INFO_GLOB_PATTERN)
+    .map_to_error_log("Failed to set up glob matcher for Nix IDE product info")
+  else {
+    return vec![];
+  };
+
+  launcher_dirs()
+    .iter()
+    .flat_map(|dir| {
+      KNOWN_LAUNCHERS
+        .iter()
+        .map(move |name| (dir.join(name), *name))
+    })
+    .filter(|(path, _)| path.is_file())
+    .collect::<Vec<_>>()
+    .into_par_iter()
+    .filter_map(|(launcher_path, launcher_name)| {
+      debug!("Resolving IDE from {:?} launcher...", &launcher_path);
+      let product_info_path = find_product_info_near(&launcher_path)
+        .or_else(|| find_nix_product_info(&glob, &launcher_path, launcher_name))?;
+      let install_root = launcher_path.parent()?;
+      let mut ide = ide_from_product_info(product_info_path, install_root, base_dirs)?;
+
+      // Use the wrapper itself instead of the IDE launcher, as Nix wrappers set up the environment
+      debug!("Using {:?} as launcher", &launcher_path);
+      ide.launcher_path = launcher_path;
+      Some(ide)
+    })
+    .collect()
+}
+
+pub fn sources() -> Vec<PathBuf> {
+  launcher_dirs()
+}
+
+fn launcher_dirs() -> Vec<PathBuf> {
+  let user = env::var("USER").unwrap_or_default();
+
+  env::var_os("PATH")
+    .map(|path| env::split_paths(&path).collect::<Vec<_>>())
+    .unwrap_or_default()
+    .into_iter()
+    .chain(
+      NIX_PROFILE_DIRS
+        .iter()
+        .map(|dir| dir.replace("$USER", &user).resolve().to_path_buf()),
+    )
+    .unique_by(|dir| canonicalize(dir).unwrap_or_else(|_| dir.clone()))
+    .filter(|dir| dir.is_dir())
+    .collect()
+}
+
+fn find_nix_product_info(
+  glob: &Glob,
+  launcher_path: &Path,
+  launcher_name: &str,
+) -> Option<PathBuf> {
+  let canonical_path = canonicalize(launcher_path).ok()?;
+
+  // Wrappers generated by nixpkgs are scripts referencing the actual package in the Nix store,
+  // which contains the IDE in a subdirectory (e.g. "<store path>/idea-ultimate/product-info.json")
+  let referenced_store_paths = read_to_string(&canonical_path)
+    .map(|script| {
+      script
+        .match_indices(NIX_STORE_DIR)
+        .filter_map(|(index, _)| {
+          script[index + NIX_STORE_DIR.len()..]
+            .split(|c: char| c == '/' || c.is_whitespace() || "\"':;".contains(c))
+            .next()
+            .map(|name| Path::new(NIX_STORE_DIR).join(name))
+        })
+        .collect::<Vec<_>>()
+    })
+    .unwrap_or_default();
+
+  nix_store_path(&canonical_path)
+    .into_iter()
+    .chain(referenced_store_paths)
+    .unique()
+    .filter(|store_path| {
+      store_path
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().contains(launcher_name))
+    })
+    .find_map(|store_path| {
+      debug!("Looking for IDE product info in {:?}...", &store_path);
+      glob
+        .walk_with_behavior(
+          &store_path,
+          WalkBehavior {
+            depth: PRODUCT_INFO_MAX_DEPTH,
+            link: LinkBehavior::ReadTarget,
+          },
+        )
+        .flatten()
+        .map(WalkEntry::into_path)
+        .next()
+    })
+}
+
+fn nix_store_path(path: &Path) -> Option<PathBuf> {
+  let relative_path = path.strip_prefix(NIX_STORE_DIR).ok()?;
+
+  match relative_path.components().next()? {
+    Component::Normal(name) => Some(Path::new(NIX_STORE_DIR).join(name)),
+    _ => None,
+  }
+}
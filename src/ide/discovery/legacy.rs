@@ -0,0 +1,179 @@
+use std::env;
+use std::fs::{read_dir, read_to_string};
+use std::path::Path;
+
+use glib::debug;
+
+use crate::ide::data::IDEData;
+use crate::ide::discovery::{ide_from_parsed_product_info, BaseDirs};
+use crate::ide::product_info::{IDELaunchSettings, IDEProductInfo};
+use crate::ide::IDEType;
+use crate::G_LOG_DOMAIN;
+
+// Product code, data directory name prefix and launcher name of IDEs predating "product-info.json"
+static LEGACY_PRODUCTS: [(&str, &str, &str); 17] = [
+  ("IU", "IntelliJIdea", "idea"),
+  ("IC", "IdeaIC", "idea"),
+  ("IE", "IdeaIE", "idea"),
+  ("PY", "PyCharm", "pycharm"),
+  ("PC", "PyCharmCE", "pycharm"),
+  ("PE", "PyCharmEdu", "pycharm"),
+  ("CL", "CLion", "clion"),
+  ("WS", "WebStorm", "webstorm"),
+  ("PS", "PhpStorm", "phpstorm"),
+  ("GO", "GoLand", "goland"),
+  ("RM", "RubyMine", "rubymine"),
+  ("DB", "DataGrip", "datagrip"),
+  ("DS", "DataSpell", "dataspell"),
+  ("RD", "Rider", "rider"),
+  ("RR", "RustRover", "rustrover"),
+  ("QA", "Aqua", "aqua"),
+  ("AI", "AndroidStudio", "studio"),
+];
+
+pub fn ide_from_build_file<A, B>(
+  build_file_path: A,
+  install_root: B,
+  base_dirs: &BaseDirs,
+) -> Option<IDEData>
+where
+  A: AsRef<Path>,
+  B: AsRef<Path>,
+{
+  let build_file_path = build_file_path.as_ref();
+  let install_dir = build_file_path.parent()?;
+
+  debug!("Parsing legacy IDE data from {:?} file", build_file_path);
+  let product_info = product_info_from_build_file(build_file_path, install_dir)?;
+
+  ide_from_parsed_product_info(&product_info, install_dir, install_root, base_dirs)
+}
+
+fn product_info_from_build_file(
+  build_file_path: &Path,
+  install_dir: &Path,
+) -> Option<IDEProductInfo> {
+  // The build file contains the product code followed by the build number (e.g. "IU-233.1234.5")
+  let raw = read_to_string(build_file_path).ok()?;
+  let Some((product_code, build_number)) = raw.trim().split_once('-') else {
+    debug!(
+      "Ignoring {:?} file, {:?} is not a valid build number",
+      build_file_path,
+      raw.trim()
+    );
+    return None;
+  };
+
+  let Some((_, data_directory_prefix, launcher_name)) = LEGACY_PRODUCTS
+    .iter()
+    .find(|(code, _, _)| *code == product_code)
+  else {
+    debug!(
+      "Ignoring {:?} file, unable to derive data directory name for {:?} product",
+      build_file_path, product_code
+    );
+    return None;
+  };
+
+  let version = version_from_build_number(product_code, build_number)?;
+  let launcher_path = find_launcher_script(install_dir, launcher_name)?;
+  let icon_path = find_icon(install_dir, launcher_name).unwrap_or_default();
+
+  Some(IDEProductInfo {
+    name: IDEType::from_product_code(product_code)?.to_string(),
+    version: version.clone(),
+    version_suffix: None,
+    build_number: build_number.to_owned(),
+    product_code: product_code.to_owned(),
+    data_directory_name: format!("{data_directory_prefix}{version}"),
+    env_var_base_name: None,
+    svg_icon_path: icon_path,
+    launch_settings: vec![IDELaunchSettings {
+      os: env::consts::OS.to_owned(),
+      arch: None,
+      launcher_path,
+      java_executable_path: None,
+      vm_options_file_path: None,
+      startup_wm_class: None,
+      boot_class_path_jar_names: vec![],
+      additional_jvm_arguments: vec![],
+    }],
+  })
+}
+
+fn version_from_build_number(product_code: &str, build_number: &str) -> Option<String> {
+  let components = build_number.split('.').collect::<Vec<_>>();
+
+  // Android Studio keeps its own version in the fourth component (e.g. "40" for 4.0),
+  // other IDEs encode it in the branch number (e.g. "193" for 2019.3)
+  if product_code == "AI" {
+    let mut digits = components.get(3)?.chars().map(|c| c.to_digit(10));
+    let (major, minor) = (digits.next()??, digits.next()??);
+
+    return Some(format!("{major}.{minor}"));
+  }
+
+  let branch = components.first()?.parse::<u32>().ok()?;
+  Some(format!("{}.{}", 2000 + branch / 10, branch % 10))
+}
+
+fn find_launcher_script(install_dir: &Path, launcher_name: &str) -> Option<String> {
+  let launcher_path = Path::new("bin").join(format!("{launcher_name}.sh"));
+
+  if install_dir.join(&launcher_path).is_file() {
+    Some(launcher_path.to_string_lossy().to_string())
+  } else {
+    debug!(
+      "Ignoring legacy IDE in {:?}, launcher script {:?} not found",
+      install_dir, launcher_path
+    );
+    None
+  }
+}
+
+fn find_icon(install_dir: &Path, launcher_name: &str) -> Option<String> {
+  let icons = read_dir(install_dir.join("bin"))
+    .ok()?
+    .flatten()
+    .map(|entry| entry.file_name().to_string_lossy().to_string())
+    .filter(|name| name.starts_with(launcher_name))
+    .collect::<Vec<_>>();
+
+  ["svg", "png"].iter().find_map(|extension| {
+    icons
+      .iter()
+      .find(|name| name.ends_with(&format!(".{extension}")))
+      .map(|name| format!("bin/{name}"))
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn derives_version_from_intellij_build_numbers() {
+    assert_eq!(
+      version_from_build_number("IU", "193.7288.26").as_deref(),
+      Some("2019.3")
+    );
+    assert_eq!(
+      version_from_build_number("PC", "201.6668.115").as_deref(),
+      Some("2020.1")
+    );
+    assert_eq!(version_from_build_number("IU", "SNAPSHOT"), None);
+  }
+
+  #[test]
+  fn derives_version_from_android_studio_build_numbers() {
+    assert_eq!(
+      version_from_build_number("AI", "193.6911.18.40.6514223").as_deref(),
+      Some("4.0")
+    );
+    assert_eq!(
+      version_from_build_number("AI", "191.8026.42.35.6010548").as_deref(),
+      Some("3.5")
+    );
+    assert_eq!(version_from_build_number("AI", "193.6911.18"), None);
+  }
+}
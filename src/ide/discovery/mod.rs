@@ -0,0 +1,240 @@
+use std::env;
+use std::fs::canonicalize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use glib::debug;
+use itertools::Itertools;
+use resolve_path::PathResolveExt;
+
+use crate::config::Config;
+use crate::ide::data::{IDEData, IDEPaths};
+use crate::ide::product_info::IDEProductInfo;
+use crate::ide::properties::IDEProperties;
+use crate::ide::IDEType;
+use crate::G_LOG_DOMAIN;
+
+mod desktop;
+mod flatpak;
+mod install_dir;
+mod launchers;
+mod legacy;
+mod snap;
+mod toolbox;
+
+static PRODUCT_INFO_FILE_NAME: &str = "product-info.json";
+static XDG_LAYOUT_BRANCH: u32 = 201;
+pub(super) static PRODUCT_INFO_MAX_DEPTH: usize = 3;
+
+pub fn discover_ides(config: &Config) -> Vec<Arc<IDEData>> {
+  let base_dirs = BaseDirs::from_xdg();
+
+  let desktop_entries = desktop::find_desktop_entries(&base_dirs);
+
+  // Sources providing richer data go first, as only the first occurrence of an IDE is kept
+  let mut ides = toolbox::discover(&base_dirs)
+    .into_iter()
+    .chain(flatpak::discover(&base_dirs))
+    .chain(snap::discover(&base_dirs))
+    .chain(launchers::discover(&base_dirs))
+    .chain(desktop::discover(&desktop_entries, &base_dirs))
+    .chain(install_dir::discover(config, &base_dirs))
+    .unique_by(|ide| canonicalize(&ide.install_dir).unwrap_or_else(|_| ide.install_dir.clone()))
+    .collect::<Vec<_>>();
+
+  desktop::apply_desktop_icons(&mut ides, &desktop_entries);
+
+  ides
+    .into_iter()
+    .inspect(|ide| {
+      debug!(
+        "Found {} {} ({}) in {:?} (install root: {:?})",
+        ide.display_name(),
+        &ide.version,
+        &ide.channel,
+        &ide.install_dir,
+        &ide.install_root
+      )
+    })
+    .map(Arc::new)
+    .collect()
+}
+
+// Files and directories whose modification may change the result of the discovery
+pub fn discovery_sources(config: &Config) -> Vec<PathBuf> {
+  let base_dirs = BaseDirs::from_xdg();
+
+  toolbox::sources(&base_dirs)
+    .into_iter()
+    .chain(flatpak::sources(&base_dirs))
+    .chain(snap::sources())
+    .chain(launchers::sources())
+    .chain(desktop::sources(&base_dirs))
+    .chain(install_dir::sources(config, &base_dirs))
+    .unique()
+    .collect()
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct BaseDirs {
+  pub home: PathBuf,
+  pub config_home: PathBuf,
+  pub cache_home: PathBuf,
+  pub data_home: PathBuf,
+}
+
+impl BaseDirs {
+  pub fn from_xdg() -> Self {
+    // The "dirs" crate follows the XDG base directory spec, falling back to its defaults
+    let base_dirs = Self {
+      home: dirs::home_dir().unwrap_or_else(|| "~".resolve().to_path_buf()),
+      config_home: dirs::config_dir().unwrap_or_else(|| "~/.config".resolve().to_path_buf()),
+      cache_home: dirs::cache_dir().unwrap_or_else(|| "~/.cache".resolve().to_path_buf()),
+      data_home: dirs::data_dir().unwrap_or_else(|| "~/.local/share".resolve().to_path_buf()),
+    };
+
+    debug!("Using {:?} as the XDG config home.", &base_dirs.config_home);
+    debug!("Using {:?} as the XDG cache home.", &base_dirs.cache_home);
+    debug!("Using {:?} as the XDG data home.", &base_dirs.data_home);
+    base_dirs
+  }
+}
+
+pub(super) fn find_program<T: AsRef<Path>>(program: T) -> Option<PathBuf> {
+  let program = program.as_ref();
+
+  if program.is_absolute() {
+    return Some(program.to_path_buf());
+  }
+
+  env::split_paths(&env::var_os("PATH")?)
+    .map(|dir| dir.join(program))
+    .find(|path| path.is_file())
+}
+
+pub(super) fn find_product_info_near<T: AsRef<Path>>(launcher_path: T) -> Option<PathBuf> {
+  // Launchers are usually located in the "bin" directory of an install, possibly behind a symlink
+  canonicalize(launcher_path)
+    .ok()?
+    .ancestors()
+    .skip(1)
+    .take(PRODUCT_INFO_MAX_DEPTH)
+    .map(|dir| dir.join(PRODUCT_INFO_FILE_NAME))
+    .find(|path| path.is_file())
+}
+
+pub(super) fn ide_from_product_info<A, B>(
+  product_info_path: A,
+  install_root: B,
+  base_dirs: &BaseDirs,
+) -> Option<IDEData>
+where
+  A: AsRef<Path>,
+  B: AsRef<Path>,
+{
+  let product_info_path = product_info_path.as_ref();
+
+  debug!("Parsing IDE data from {:?} file", product_info_path);
+  let install_dir = product_info_path.parent()?;
+  let product_info = IDEProductInfo::from_file(product_info_path).ok()?;
+
+  ide_from_parsed_product_info(&product_info, install_dir, install_root, base_dirs)
+}
+
+pub(super) fn ide_from_parsed_product_info<A, B>(
+  product_info: &IDEProductInfo,
+  install_dir: A,
+  install_root: B,
+  base_dirs: &BaseDirs,
+) -> Option<IDEData>
+where
+  A: AsRef<Path>,
+  B: AsRef<Path>,
+{
+  let install_dir = install_dir.as_ref();
+  let paths = resolve_ide_paths(product_info, install_dir, base_dirs);
+
+  IDEData::from_product_info(product_info, install_dir, install_root, paths).ok()
+}
+
+fn resolve_ide_paths(
+  product_info: &IDEProductInfo,
+  install_dir: &Path,
+  base_dirs: &BaseDirs,
+) -> IDEPaths {
+  let vendor = if product_info.product().ide_type == IDEType::AndroidStudio {
+    "Google"
+  } else {
+    "JetBrains"
+  };
+  // IDEs before 2020.1 kept all their data in a single "~/.<data directory name>" directory
+  let is_legacy =
+    build_branch(&product_info.build_number).is_some_and(|branch| branch < XDG_LAYOUT_BRANCH);
+  let legacy_dir = is_legacy.then(|| {
+    base_dirs
+      .home
+      .join(format!(".{}", &product_info.data_directory_name))
+  });
+  let default_config_path = match &legacy_dir {
+    Some(legacy_dir) => legacy_dir.join("config"),
+    None => base_dirs
+      .config_home
+      .join(vendor)
+      .join(&product_info.data_directory_name),
+  };
+
+  // The base name of the "<PRODUCT>_PROPERTIES" env var, older IDEs only declare it in their launcher
+  let env_var_base_name = product_info.env_var_base_name.clone().unwrap_or_else(|| {
+    product_info
+      .current_launch_settings()
+      .and_then(|settings| Path::new(&settings.launcher_path).file_stem())
+      .map(|stem| stem.to_string_lossy().to_string())
+      .unwrap_or_else(|| product_info.product_code.clone())
+  });
+  let props = IDEProperties::load(&env_var_base_name, install_dir, &default_config_path);
+
+  let config_path = props.config_path();
+  let system_path = props.system_path().unwrap_or_else(|| match &legacy_dir {
+    Some(legacy_dir) => legacy_dir.join("system"),
+    None => base_dirs
+      .cache_home
+      .join(vendor)
+      .join(&product_info.data_directory_name),
+  });
+  // Relocating the config directory also relocates the plugins directory into it
+  let plugins_path = props.plugins_path().unwrap_or_else(|| match &config_path {
+    Some(config_path) => config_path.join("plugins"),
+    None if legacy_dir.is_some() => default_config_path.join("plugins"),
+    None => base_dirs
+      .data_home
+      .join(vendor)
+      .join(&product_info.data_directory_name),
+  });
+  let log_path = props.log_path().unwrap_or_else(|| system_path.join("log"));
+
+  if config_path.is_none() {
+    debug!("Config path setting not found, using default...");
+  }
+
+  let paths = IDEPaths {
+    config_path: config_path.unwrap_or(default_config_path),
+    system_path,
+    plugins_path,
+    log_path,
+  };
+
+  debug!("Using {:?} as the config path.", &paths.config_path);
+  debug!("Using {:?} as the system path.", &paths.system_path);
+  debug!("Using {:?} as the plugins path.", &paths.plugins_path);
+  debug!("Using {:?} as the log path.", &paths.log_path);
+  paths
+}
+
+fn build_branch(build_number: &str) -> Option<u32> {
+  // The branch is the first component of the build number (e.g. "193" in "IU-193.7288.26")
+  let build_number = build_number
+    .split_once('-')
+    .map_or(build_number, |(_, build_number)| build_number);
+
+  build_number.split('.').next()?.parse().ok()
+}
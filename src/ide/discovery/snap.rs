@@ -0,0 +1,105 @@
+use std::path::{Path, PathBuf};
+
+use glib::{debug, warn};
+use rayon::prelude::*;
+use resolve_path::PathResolveExt;
+
+use crate::ide::data::IDEData;
+use crate::ide::discovery::{ide_from_product_info, BaseDirs};
+use crate::ide::IDEType;
+use crate::G_LOG_DOMAIN;
+
+static SNAP_INSTALL_DIR: &str = "/snap/";
+static SNAP_BIN_DIR: &str = "/snap/bin/";
+
+static KNOWN_SNAPS: [(&str, IDEType); 16] = [
+  ("intellij-idea-ultimate", IDEType::IntelliJIDEA),
+  ("intellij-idea-community", IDEType::IntelliJIDEA),
+  ("intellij-idea-educational", IDEType::IntelliJIDEA),
+  ("pycharm-professional", IDEType::PyCharm),
+  ("pycharm-community", IDEType::PyCharm),
+  ("pycharm-educational", IDEType::PyCharm),
+  ("clion", IDEType::CLion),
+  ("goland", IDEType::GoLand),
+  ("webstorm", IDEType::WebStorm),
+  ("phpstorm", IDEType::PHPStorm),
+  ("rubymine", IDEType::RubyMine),
+  ("datagrip", IDEType::DataGrip),
+  ("dataspell", IDEType::DataSpell),
+  ("rider", IDEType::Rider),
+  ("rustrover", IDEType::RustRover),
+  ("android-studio", IDEType::AndroidStudio),
+];
+
+pub fn sources() -> Vec<PathBuf> {
+  vec![PathBuf::from(SNAP_INSTALL_DIR), PathBuf::from(SNAP_BIN_DIR)]
+}
+
+pub fn discover(base_dirs: &BaseDirs) -> Vec<IDEData> {
+  let install_root = Path::new(SNAP_INSTALL_DIR);
+
+  if !install_root.is_dir() {
+    debug!(
+      "Skipping snap install root {:?}, the directory doesn't exist",
+      install_root
+    );
+    return vec![];
+  }
+
+  KNOWN_SNAPS
+    .par_iter()
+    .filter_map(|(snap_name, ide_type)| {
+      let install_dir = install_root.join(snap_name).join("current");
+      let product_info_path = install_dir.join("product-info.json");
+
+      if !product_info_path.is_file() {
+        return None;
+      }
+
+      debug!("Found {} snap {:?}", ide_type, snap_name);
+      let mut ide = ide_from_product_info(
+        &product_info_path,
+        install_root,
+        &snap_base_dirs(snap_name, base_dirs),
+      )?;
+
+      if &ide.ide_type != ide_type {
+        warn!(
+          "Snap {:?} was expected to contain {}, but {} was found instead",
+          snap_name,
+          ide_type,
+          ide.display_name()
+        );
+      }
+
+      let launcher_path = Path::new(SNAP_BIN_DIR).join(snap_name);
+      if launcher_path.exists() {
+        debug!("Using snap wrapper {:?} as launcher", &launcher_path);
+        ide.launcher_path = launcher_path;
+      }
+
+      Some(ide)
+    })
+    .collect()
+}
+
+fn snap_base_dirs(snap_name: &str, base_dirs: &BaseDirs) -> BaseDirs {
+  // Strictly confined snaps have their HOME redirected to "~/snap/<name>/current",
+  // classic snaps (which most JetBrains IDEs are) use the regular base directories
+  let snap_home = Path::new("~/snap/")
+    .join(snap_name)
+    .join("current")
+    .resolve()
+    .to_path_buf();
+
+  if snap_home.join(".config").is_dir() {
+    BaseDirs {
+      home: snap_home.clone(),
+      config_home: snap_home.join(".config"),
+      cache_home: snap_home.join(".cache"),
+      data_home: snap_home.join(".local/share"),
+    }
+  } else {
+    base_dirs.clone()
+  }
+}
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use glib::{debug, warn};
+use rayon::prelude::*;
+use resolve_path::PathResolveExt;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::ide::data::IDEData;
+use crate::ide::discovery::{ide_from_product_info, BaseDirs};
+use crate::ide::IDEChannel;
+use crate::G_LOG_DOMAIN;
+
+static TOOLBOX_DIR: &str = "JetBrains/Toolbox/";
+static TOOLBOX_STATE_FILE: &str = "state.json";
+static TOOLBOX_SETTINGS_FILE: &str = ".settings.json";
+
+#[derive(Deserialize, Debug)]
+struct ToolboxState {
+  #[serde(default)]
+  tools: Vec<ToolboxTool>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ToolboxTool {
+  channel_id: String,
+  tool_id: String,
+  #[serde(default)]
+  channel: Option<String>,
+  #[serde(default)]
+  display_version: Option<String>,
+  install_location: PathBuf,
+  #[serde(default)]
+  launch_command: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct ToolboxSettings {
+  #[serde(default)]
+  install_location: Option<PathBuf>,
+  #[serde(default)]
+  shell_scripts: ToolboxShellScripts,
+  #[serde(default)]
+  tools: HashMap<String, ToolboxToolSettings>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ToolboxShellScripts {
+  #[serde(default = "default_shell_scripts_enabled")]
+  enabled: bool,
+  #[serde(default)]
+  location: Option<PathBuf>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct ToolboxToolSettings {
+  #[serde(default)]
+  name: Option<String>,
+  #[serde(default)]
+  shell_script_name: Option<String>,
+  #[serde(default)]
+  channel: Option<String>,
+}
+
+impl Default for ToolboxShellScripts {
+  fn default() -> Self {
+    Self {
+      enabled: default_shell_scripts_enabled(),
+      location: None,
+    }
+  }
+}
+
+fn default_shell_scripts_enabled() -> bool {
+  true
+}
+
+pub fn sources(base_dirs: &BaseDirs) -> Vec<PathBuf> {
+  let toolbox_dir = base_dirs.data_home.join(TOOLBOX_DIR);
+
+  vec![
+    toolbox_dir.join(TOOLBOX_STATE_FILE),
+    toolbox_dir.join(TOOLBOX_SETTINGS_FILE),
+  ]
+}
+
+pub fn discover(base_dirs: &BaseDirs) -> Vec<IDEData> {
+  let toolbox_dir = base_dirs.data_home.join(TOOLBOX_DIR);
+  let state_path = toolbox_dir.join(TOOLBOX_STATE_FILE);
+
+  debug!("Looking for Toolbox state file at {:?}...", &state_path);
+  let Some(state) = read_json_file::<ToolboxState>(&state_path) else {
+    return vec![];
+  };
+  let settings =
+    read_json_file::<ToolboxSettings>(toolbox_dir.join(TOOLBOX_SETTINGS_FILE)).unwrap_or_default();
+
+  let install_root = settings
+    .install_location
+    .as_ref()
+    .map(|path| path.resolve().to_path_buf())
+    .unwrap_or_else(|| toolbox_dir.clone());
+  let scripts_dir = settings
+    .shell_scripts
+    .location
+    .as_ref()
+    .map(|path| path.resolve().to_path_buf())
+    .unwrap_or_else(|| toolbox_dir.join("scripts"));
+  debug!("Using {:?} as the Toolbox install location.", &install_root);
+  debug!(
+    "Using {:?} as the Toolbox shell scripts location.",
+    &scripts_dir
+  );
+
+  state
+    .tools
+    .par_iter()
+    .filter_map(|tool| {
+      debug!(
+        "Resolving Toolbox tool {:?} from {:?}...",
+        &tool.tool_id, &tool.install_location
+      );
+      let tool_settings = settings.tools.get(&tool.channel_id);
+      let install_dir = tool.install_location.resolve();
+      let mut ide = ide_from_product_info(
+        install_dir.join("product-info.json"),
+        &install_root,
+        base_dirs,
+      )?;
+
+      ide.channel = tool_settings
+        .and_then(|settings| settings.channel.as_ref())
+        .or(tool.channel.as_ref())
+        .and_then(IDEChannel::from_name)
+        .or_else(|| channel_from_version(tool.display_version.as_ref()?))
+        .unwrap_or(ide.channel);
+      ide.custom_name = tool_settings.and_then(|settings| settings.name.clone());
+
+      if settings.shell_scripts.enabled {
+        let script_name = tool_settings
+          .and_then(|settings| settings.shell_script_name.clone())
+          .or_else(|| default_script_name(tool.launch_command.as_ref()?));
+
+        if let Some(script_path) = script_name
+          .map(|name| scripts_dir.join(name))
+          .filter(|path| path.is_file())
+        {
+          debug!("Using Toolbox shell script {:?} as launcher", &script_path);
+          ide.launcher_path = script_path;
+        }
+      }
+
+      Some(ide)
+    })
+    .collect()
+}
+
+fn channel_from_version(display_version: &str) -> Option<IDEChannel> {
+  display_version
+    .split_whitespace()
+    .skip(1)
+    .find_map(IDEChannel::from_name)
+}
+
+fn default_script_name(launch_command: &str) -> Option<String> {
+  Path::new(launch_command)
+    .file_stem()
+    .map(|stem| stem.to_string_lossy().to_string())
+}
+
+fn read_json_file<T: DeserializeOwned>(path: impl AsRef<Path>) -> Option<T> {
+  let file = File::open(path.as_ref())
+    .map_err(|err| match err {
+      _ if err.kind() == ErrorKind::NotFound => {}
+      _ if err.kind() == ErrorKind::PermissionDenied => warn!(
+        "Failed to read {:?} file, insufficient permissions",
+        path.as_ref()
+      ),
+      _ => warn!("Failed to read {:?} file, reason: {}", path.as_ref(), err),
+    })
+    .ok()?;
+  let reader = BufReader::new(file);
+
+  serde_json::from_reader(reader)
+    .map_err(|err| warn!("Failed to parse {:?} file, reason: {}", path.as_ref(), err))
+    .ok()
+}
@@ -1,14 +1,19 @@
 use std::fmt::{Display, Formatter};
 
+use serde::{Deserialize, Serialize};
 use strum::EnumIter;
 
+use crate::ide::data::IDEData;
+
 pub mod data;
-mod de;
+pub mod discovery;
+pub mod process;
 pub mod product_info;
 pub mod properties;
+pub mod version;
 
 #[allow(clippy::upper_case_acronyms)]
-#[derive(Debug, Clone, PartialEq, Eq, Hash, EnumIter)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize)]
 pub enum IDEType {
   Aqua,
   CLion,
@@ -24,6 +29,11 @@ pub enum IDEType {
   AndroidStudio,
   RustRover,
   MPS,
+  #[strum(disabled)]
+  Other {
+    code: String,
+    name: String,
+  },
 }
 
 impl IDEType {
@@ -35,10 +45,10 @@ impl IDEType {
       "RR" => IDEType::RustRover,
       "WS" => IDEType::WebStorm,
       "RM" => IDEType::RubyMine,
-      "PY" | "PC" => IDEType::PyCharm,
+      "PY" | "PC" | "PE" => IDEType::PyCharm,
       "PS" => IDEType::PHPStorm,
       "MPS" => IDEType::MPS,
-      "IU" | "IC" => IDEType::IntelliJIDEA,
+      "IU" | "IC" | "IE" => IDEType::IntelliJIDEA,
       "DS" => IDEType::DataSpell,
       "DB" => IDEType::DataGrip,
       "QA" => IDEType::Aqua,
@@ -65,12 +75,128 @@ impl IDEType {
       IDEType::AndroidStudio => "android-studio",
       IDEType::RustRover => "rustrover",
       IDEType::MPS => "mps",
+      IDEType::Other { code, .. } => return (code.to_lowercase(), self.clone()),
     };
 
     (alias.to_owned(), self.clone())
   }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum IDEEdition {
+  Ultimate,
+  Professional,
+  Community,
+  Educational,
+}
+
+impl IDEEdition {
+  pub fn from_product_code<T: AsRef<str>>(code: T) -> Option<Self> {
+    let enum_value = match code.as_ref() {
+      "IU" => IDEEdition::Ultimate,
+      "PY" => IDEEdition::Professional,
+      "IC" | "PC" => IDEEdition::Community,
+      "IE" | "PE" => IDEEdition::Educational,
+      _ => return None,
+    };
+
+    Some(enum_value)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IDEProduct {
+  pub code: String,
+  pub ide_type: IDEType,
+}
+
+impl IDEProduct {
+  pub fn new<A, B>(code: A, name: B) -> Self
+  where
+    A: AsRef<str>,
+    B: AsRef<str>,
+  {
+    let code = code.as_ref();
+
+    Self {
+      code: code.to_owned(),
+      ide_type: IDEType::from_product_code(code).unwrap_or_else(|| IDEType::Other {
+        code: code.to_owned(),
+        name: name.as_ref().to_owned(),
+      }),
+    }
+  }
+
+  pub fn from_product_code<T: AsRef<str>>(code: T) -> Option<Self> {
+    Some(Self {
+      code: code.as_ref().to_owned(),
+      ide_type: IDEType::from_product_code(code)?,
+    })
+  }
+
+  pub fn edition(&self) -> Option<IDEEdition> {
+    IDEEdition::from_product_code(&self.code)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IDEFilter {
+  Type(IDEType),
+  Product(String),
+}
+
+impl IDEFilter {
+  pub fn from_product_code<T: AsRef<str>>(code: T) -> Option<Self> {
+    let product = IDEProduct::from_product_code(code)?;
+
+    // Only target a specific product when it's one of multiple editions of the same IDE
+    if product.edition().is_some() {
+      Some(IDEFilter::Product(product.code))
+    } else {
+      Some(IDEFilter::Type(product.ide_type))
+    }
+  }
+
+  pub fn from_any_product_code<T: AsRef<str>>(code: T) -> Option<Self> {
+    let code = code.as_ref();
+
+    // Products unknown to the plugin can only be targeted by their exact product code
+    Self::from_product_code(code).or_else(|| {
+      (!code.is_empty() && code.chars().all(|c| c.is_ascii_alphanumeric()))
+        .then(|| IDEFilter::Product(code.to_owned()))
+    })
+  }
+
+  pub fn matches(&self, ide: &IDEData) -> bool {
+    match self {
+      IDEFilter::Type(ide_type) => &ide.ide_type == ide_type,
+      IDEFilter::Product(code) => &ide.product_code == code,
+    }
+  }
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum IDEChannel {
+  #[default]
+  Stable,
+  EAP,
+  Nightly,
+}
+
+impl IDEChannel {
+  pub fn from_name<T: AsRef<str>>(name: T) -> Option<Self> {
+    let enum_value = match name.as_ref().to_lowercase().as_str() {
+      "stable" | "release" | "rc" => IDEChannel::Stable,
+      "eap" | "preview" | "beta" => IDEChannel::EAP,
+      "nightly" => IDEChannel::Nightly,
+      _ => return None,
+    };
+
+    Some(enum_value)
+  }
+}
+
 impl Display for IDEType {
   fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
     let name = match self {
@@ -88,6 +214,41 @@ impl Display for IDEType {
       IDEType::AndroidStudio => "Android Studio",
       IDEType::RustRover => "RustRover",
       IDEType::MPS => "MPS",
+      IDEType::Other { name, .. } => name,
+    };
+
+    write!(f, "{name}")
+  }
+}
+
+impl Display for IDEEdition {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    let name = match self {
+      IDEEdition::Ultimate => "Ultimate",
+      IDEEdition::Professional => "Professional",
+      IDEEdition::Community => "Community",
+      IDEEdition::Educational => "Educational",
+    };
+
+    write!(f, "{name}")
+  }
+}
+
+impl Display for IDEFilter {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      IDEFilter::Type(ide_type) => write!(f, "{ide_type}"),
+      IDEFilter::Product(code) => write!(f, "{code}"),
+    }
+  }
+}
+
+impl Display for IDEChannel {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    let name = match self {
+      IDEChannel::Stable => "Stable",
+      IDEChannel::EAP => "EAP",
+      IDEChannel::Nightly => "Nightly",
     };
 
     write!(f, "{name}")
@@ -0,0 +1,57 @@
+use std::fs::{canonicalize, read, read_dir, read_link, read_to_string};
+use std::path::Path;
+
+use crate::ide::data::IDEData;
+
+static PROC_DIR: &str = "/proc/";
+static FLATPAK_INFO_PATH: &str = "root/.flatpak-info";
+
+pub fn is_ide_running(ide: &IDEData) -> bool {
+  let Ok(processes) = read_dir(PROC_DIR) else {
+    return false;
+  };
+  let mut processes = processes.flatten().filter(|entry| {
+    entry
+      .file_name()
+      .to_string_lossy()
+      .chars()
+      .all(|c| c.is_ascii_digit())
+  });
+
+  // Sandboxed IDEs see their install directory under "/app", so they are recognized by the app ID
+  if let Some(flatpak_id) = &ide.flatpak_id {
+    return processes
+      .map(|entry| entry.path())
+      .any(|process_dir| runs_in_flatpak(&process_dir, flatpak_id));
+  }
+
+  let Ok(install_dir) = canonicalize(&ide.install_dir) else {
+    return false;
+  };
+
+  // IDEs run either their native launcher or a JVM loading classes from the install directory
+  processes.any(|entry| {
+    let process_dir = entry.path();
+    let runs_from_install_dir = read_link(process_dir.join("exe"))
+      .is_ok_and(|executable| executable.starts_with(&install_dir));
+
+    runs_from_install_dir
+      || read(process_dir.join("cmdline")).is_ok_and(|raw| {
+        // Paths are also passed within options and class paths (e.g. "-Didea.home=<path>")
+        String::from_utf8_lossy(&raw)
+          .split(['\0', '=', ':'])
+          .any(|arg| Path::new(arg).starts_with(&install_dir))
+      })
+  })
+}
+
+fn runs_in_flatpak(process_dir: &Path, flatpak_id: &str) -> bool {
+  let app_name = format!("name={flatpak_id}");
+  let app_scope = format!("app-flatpak-{flatpak_id}-");
+
+  // Flatpak places its metadata into the root of every sandbox, the cgroup is a fallback for
+  // processes whose root isn't accessible
+  read_to_string(process_dir.join(FLATPAK_INFO_PATH))
+    .is_ok_and(|info| info.lines().any(|line| line.trim() == app_name))
+    || read_to_string(process_dir.join("cgroup")).is_ok_and(|cgroup| cgroup.contains(&app_scope))
+}
@@ -1,18 +1,52 @@
+use std::env;
 use std::fs::File;
 use std::io::{BufReader, ErrorKind};
 use std::path::Path;
 
-use glib::warn;
+use glib::{debug, warn};
 use serde::Deserialize;
 
-use crate::ide::IDEType;
+use crate::ide::{IDEProduct, IDEType};
 use crate::G_LOG_DOMAIN;
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[allow(dead_code)]
 pub struct IDELaunchSettings {
+  pub os: String,
+  #[serde(default)]
+  pub arch: Option<String>,
   pub launcher_path: String,
-  pub startup_wm_class: String,
+  #[serde(default)]
+  pub java_executable_path: Option<String>,
+  #[serde(default)]
+  pub vm_options_file_path: Option<String>,
+  #[serde(default)]
+  pub startup_wm_class: Option<String>,
+  #[serde(default)]
+  pub boot_class_path_jar_names: Vec<String>,
+  #[serde(default)]
+  pub additional_jvm_arguments: Vec<String>,
+}
+
+impl IDELaunchSettings {
+  fn matches_os(&self) -> bool {
+    self.os.eq_ignore_ascii_case(env::consts::OS)
+  }
+
+  fn matches_arch(&self) -> bool {
+    // Product info uses Java's architecture names
+    let current_arch = match env::consts::ARCH {
+      "x86_64" => "amd64",
+      "x86" => "i386",
+      arch => arch,
+    };
+
+    self
+      .arch
+      .as_ref()
+      .is_some_and(|arch| arch.eq_ignore_ascii_case(current_arch))
+  }
 }
 
 #[derive(Deserialize, Debug)]
@@ -21,16 +55,36 @@ pub struct IDELaunchSettings {
 pub struct IDEProductInfo {
   pub name: String,
   pub version: String,
+  #[serde(default)]
+  pub version_suffix: Option<String>,
   pub build_number: String,
-  #[serde(rename = "productCode")]
-  pub ide_type: IDEType,
+  pub product_code: String,
   pub data_directory_name: String,
+  #[serde(default)]
+  pub env_var_base_name: Option<String>,
   pub svg_icon_path: String,
   #[serde(rename = "launch")]
   pub launch_settings: Vec<IDELaunchSettings>,
 }
 
 impl IDEProductInfo {
+  pub fn product(&self) -> IDEProduct {
+    IDEProduct::new(&self.product_code, &self.name)
+  }
+
+  pub fn current_launch_settings(&self) -> Option<&IDELaunchSettings> {
+    // Entries without the architecture set are used by older IDEs and match any architecture
+    let candidates = self
+      .launch_settings
+      .iter()
+      .filter(|settings| settings.matches_os());
+
+    candidates
+      .clone()
+      .find(|settings| settings.matches_arch())
+      .or_else(|| candidates.clone().find(|settings| settings.arch.is_none()))
+  }
+
   pub fn from_file<T: AsRef<Path>>(path: T) -> Result<IDEProductInfo, ()> {
     let file = File::open(path.as_ref()).map_err(|err| match err {
       _ if err.kind() == ErrorKind::NotFound => {
@@ -49,7 +103,18 @@ impl IDEProductInfo {
     })?;
     let reader = BufReader::new(file);
 
-    serde_json::from_reader(reader)
-      .map_err(|err| warn!("Failed to parse {:?} file, reason: {}", path.as_ref(), err))
+    let product_info: IDEProductInfo = serde_json::from_reader(reader)
+      .map_err(|err| warn!("Failed to parse {:?} file, reason: {}", path.as_ref(), err))?;
+
+    if let IDEType::Other { code, name } = product_info.product().ide_type {
+      debug!(
+        "Unknown product code {:?} in {:?} file, treating it as a generic {} IDE",
+        code,
+        path.as_ref(),
+        name
+      );
+    }
+
+    Ok(product_info)
   }
 }
@@ -1,22 +1,150 @@
+use std::collections::HashMap;
+use std::env;
 use std::fs::File;
 use std::io::{BufReader, ErrorKind};
 use std::path::{Path, PathBuf};
 
-use glib::warn;
-use serde::Deserialize;
+use glib::{debug, warn};
 
-use crate::ide::de::evaluate_property;
 use crate::G_LOG_DOMAIN;
 
-#[derive(Deserialize, Debug)]
+static PROPERTIES_FILE_NAME: &str = "idea.properties";
+static PROPERTIES_ENV_VAR_SUFFIX: &str = "_PROPERTIES";
+static MAX_EVALUATION_DEPTH: usize = 8;
+
+#[derive(Debug)]
 pub struct IDEProperties {
-  #[serde(default)]
-  #[serde(rename = "idea.config.path", deserialize_with = "evaluate_property")]
-  pub config_path: Option<PathBuf>,
+  install_dir: PathBuf,
+  values: HashMap<String, String>,
+}
+
+pub fn properties_env_vars() -> Vec<(String, PathBuf)> {
+  let mut env_vars = env::vars_os()
+    .filter_map(|(name, value)| {
+      let name = name.into_string().ok()?;
+      name
+        .ends_with(PROPERTIES_ENV_VAR_SUFFIX)
+        .then(|| (name, PathBuf::from(value)))
+    })
+    .collect::<Vec<_>>();
+
+  env_vars.sort();
+  env_vars
 }
 
 impl IDEProperties {
-  pub fn from_file<T: AsRef<Path>>(path: T) -> Option<Self> {
+  pub fn load<A, B>(env_var_base_name: &str, install_dir: A, user_config_dir: B) -> Self
+  where
+    A: AsRef<Path>,
+    B: AsRef<Path>,
+  {
+    let install_dir = install_dir.as_ref();
+    let env_var = env_var_base_name.to_uppercase() + PROPERTIES_ENV_VAR_SUFFIX;
+
+    // Files are listed from the highest priority, as properties defined in them take precedence
+    let files = env::var_os(&env_var).map(PathBuf::from).into_iter().chain([
+      user_config_dir.as_ref().join(PROPERTIES_FILE_NAME),
+      install_dir.join("bin").join(PROPERTIES_FILE_NAME),
+    ]);
+
+    let mut values = HashMap::<String, String>::new();
+    for file in files {
+      debug!("Looking for {:?} file...", &file);
+
+      if let Some(properties) = Self::read_file(&file) {
+        debug!(
+          "Properties file {:?} found, merging its properties...",
+          &file
+        );
+
+        for (key, value) in properties {
+          values.entry(key).or_insert(value);
+        }
+      }
+    }
+
+    Self {
+      install_dir: install_dir.to_path_buf(),
+      values,
+    }
+  }
+
+  pub fn config_path(&self) -> Option<PathBuf> {
+    self.get_path("idea.config.path")
+  }
+
+  pub fn system_path(&self) -> Option<PathBuf> {
+    self.get_path("idea.system.path")
+  }
+
+  pub fn plugins_path(&self) -> Option<PathBuf> {
+    self.get_path("idea.plugins.path")
+  }
+
+  pub fn log_path(&self) -> Option<PathBuf> {
+    self.get_path("idea.log.path")
+  }
+
+  fn get_path(&self, key: &str) -> Option<PathBuf> {
+    let value = self.evaluate(self.values.get(key)?, 0)?;
+    let value = value.trim().trim_matches('"');
+    let path = match value.strip_prefix('~') {
+      Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+        dirs::home_dir()?.join(rest.trim_start_matches('/'))
+      }
+      _ => PathBuf::from(value),
+    };
+
+    // Relative paths are resolved against the IDE installation directory
+    Some(self.install_dir.join(path))
+  }
+
+  fn evaluate(&self, value: &str, depth: usize) -> Option<String> {
+    if depth > MAX_EVALUATION_DEPTH {
+      warn!("Failed to evaluate property expression {value:?}, the expression is too deep");
+      return None;
+    }
+
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+      let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+        break;
+      };
+
+      result.push_str(&rest[..start]);
+      let name = &rest[start + 2..end];
+      let Some(replacement) = self.resolve_variable(name, depth) else {
+        warn!("Failed to evaluate property expression {value:?}, {name:?} is not defined");
+        return None;
+      };
+      result.push_str(&replacement);
+      rest = &rest[end + 1..];
+    }
+
+    result.push_str(rest);
+    Some(result)
+  }
+
+  fn resolve_variable(&self, name: &str, depth: usize) -> Option<String> {
+    match name {
+      "user.home" => dirs::home_dir().map(|path| path.to_string_lossy().to_string()),
+      "user.name" => env::var("USER").ok(),
+      "java.io.tmpdir" => Some(env::temp_dir().to_string_lossy().to_string()),
+      "idea.home" | "idea.home.path" => Some(self.install_dir.to_string_lossy().to_string()),
+      _ => match name.strip_prefix("env.") {
+        Some(env_var) => env::var(env_var).ok(),
+        None => self
+          .values
+          .get(name)
+          .and_then(|value| self.evaluate(value, depth + 1))
+          .or_else(|| env::var(name).ok()),
+      },
+    }
+  }
+
+  fn read_file<T: AsRef<Path>>(path: T) -> Option<HashMap<String, String>> {
     let file = File::open(path.as_ref())
       .map_err(|err| match err {
         _ if err.kind() == ErrorKind::NotFound => {}
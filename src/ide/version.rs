@@ -0,0 +1,104 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::ide::data::IDEData;
+use crate::ide::{IDEChannel, IDEFilter};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct IDEBuildNumber {
+  components: Vec<u32>,
+}
+
+impl IDEBuildNumber {
+  pub fn parse<T: AsRef<str>>(raw: T) -> Self {
+    // Build numbers can be prefixed with the product code (e.g. "AI-241.15989.150")
+    let raw = raw.as_ref();
+    let raw = raw.split_once('-').map_or(raw, |(_, build)| build);
+
+    Self {
+      components: raw
+        .split('.')
+        .map_while(|component| match component {
+          "SNAPSHOT" => Some(u32::MAX),
+          _ => component.parse::<u32>().ok(),
+        })
+        .collect(),
+    }
+  }
+}
+
+impl PartialOrd for IDEBuildNumber {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for IDEBuildNumber {
+  fn cmp(&self, other: &Self) -> Ordering {
+    self.components.cmp(&other.components)
+  }
+}
+
+impl Display for IDEBuildNumber {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.components.iter().join("."))
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IDEVersionPreference {
+  Channel(IDEChannel),
+  Version(String),
+}
+
+impl IDEVersionPreference {
+  pub fn parse<T: AsRef<str>>(raw: T) -> Self {
+    let raw = raw.as_ref();
+
+    IDEChannel::from_name(raw)
+      .map(IDEVersionPreference::Channel)
+      .unwrap_or_else(|| IDEVersionPreference::Version(raw.to_owned()))
+  }
+
+  pub fn matches(&self, ide: &IDEData) -> bool {
+    match self {
+      IDEVersionPreference::Channel(channel) => &ide.channel == channel,
+      IDEVersionPreference::Version(version) => {
+        ide.version.starts_with(version) || ide.build_number.to_string().starts_with(version)
+      }
+    }
+  }
+}
+
+pub fn resolve_preferred_ides(
+  ides: &[Arc<IDEData>],
+  preferences: &[(IDEFilter, IDEVersionPreference)],
+) -> HashMap<String, Arc<IDEData>> {
+  ides
+    .iter()
+    .into_group_map_by(|ide| ide.product_code.clone())
+    .into_iter()
+    .filter_map(|(product_code, candidates)| {
+      let preference = preferences
+        .iter()
+        .find(|(filter, _)| candidates.iter().any(|ide| filter.matches(ide)))
+        .map(|(_, preference)| preference);
+
+      // User's preference wins, then stable builds are preferred over the newest ones
+      let preferred = candidates.into_iter().max_by_key(|ide| {
+        (
+          preference.is_some_and(|preference| preference.matches(ide)),
+          ide.channel == IDEChannel::Stable,
+          ide.build_number.clone(),
+        )
+      })?;
+
+      Some((product_code, Arc::clone(preferred)))
+    })
+    .collect()
+}
@@ -4,27 +4,28 @@ use std::process;
 use std::process::Command;
 use std::sync::Arc;
 
-use glib::{debug, warn, GlibLogger, GlibLoggerDomain, GlibLoggerFormat};
+use glib::{debug, markup_escape_text, warn, GlibLogger, GlibLoggerDomain, GlibLoggerFormat};
 use itertools::Itertools;
 use log::LevelFilter;
-use rayon::prelude::*;
 use resolve_path::PathResolveExt;
 use rofi_mode::cairo::Surface;
 use rofi_mode::{export_mode, Action, Api, Event, Matcher};
 use strum::IntoEnumIterator;
-use wax::{Glob, LinkBehavior, WalkEntry};
 
 use crate::config::Config;
-use crate::ide::data::IDEData;
-use crate::ide::product_info::IDEProductInfo;
-use crate::ide::properties::IDEProperties;
-use crate::ide::IDEType;
+use crate::ide::process::is_ide_running;
+use crate::ide::{IDEFilter, IDEType};
+use crate::loader::ProjectLoader;
 use crate::macros::wrap_icon_request;
-use crate::recent_project::{RecentProject, RecentProjectsParser};
-use crate::traits::MapToErrorLog;
+use crate::recent_project::editor::{relocate_project, remove_project, to_raw_path};
+use crate::recent_project::{
+  merge_projects, run_with_timeout, PathProbe, ProjectFilter, ProjectStatus, RecentProject,
+};
 
+mod cache;
 mod config;
 mod ide;
+mod loader;
 mod macros;
 mod recent_project;
 mod rofi;
@@ -32,21 +33,27 @@ mod traits;
 
 pub static G_LOG_DOMAIN: &str = "Modes.JetBrains";
 
+static GROUP_QUERY_PREFIX: char = '@';
+static MISSING_PROJECT_ACTIONS: [&str; 2] = [
+  "Remove from recent projects",
+  "Relocate to the entered path",
+];
+
 static GLIB_LOGGER: GlibLogger =
   GlibLogger::new(GlibLoggerFormat::Plain, GlibLoggerDomain::CrateTarget);
 
-static RECENT_PROJECTS_GLOB_PATTERN: &str = "options/{recentProjects,recentSolutions}.xml";
-static PRODUCT_INFO_GLOB_PATTERN: &str = "*/product-info.json";
-
 export_mode!(Mode<'_>);
 
 struct Mode<'rofi> {
   api: Api<'rofi>,
   config: Config,
+  loader: ProjectLoader,
   projects: Vec<Arc<RecentProject>>,
-  query: Option<IDEType>,
+  query: Option<ProjectFilter>,
   entries: Vec<Arc<RecentProject>>,
-  aliases: HashMap<String, IDEType>,
+  aliases: HashMap<String, IDEFilter>,
+  missing_project: Option<Arc<RecentProject>>,
+  notice: Option<String>,
 }
 
 impl<'rofi> rofi_mode::Mode<'rofi> for Mode<'rofi> {
@@ -62,146 +69,83 @@ impl<'rofi> rofi_mode::Mode<'rofi> for Mode<'rofi> {
     let config = Config::from_rofi();
 
     let predefined_custom_aliases = vec![
-      ("php".to_string(), IDEType::PHPStorm),
-      ("py".to_string(), IDEType::PyCharm),
-      ("web".to_string(), IDEType::WebStorm),
-      ("go".to_string(), IDEType::GoLand),
-      ("ruby".to_string(), IDEType::RubyMine),
-      ("studio".to_string(), IDEType::AndroidStudio),
-      ("android".to_string(), IDEType::AndroidStudio),
-      ("rust".to_string(), IDEType::RustRover),
+      ("php".to_string(), IDEFilter::Type(IDEType::PHPStorm)),
+      ("py".to_string(), IDEFilter::Type(IDEType::PyCharm)),
+      ("web".to_string(), IDEFilter::Type(IDEType::WebStorm)),
+      ("go".to_string(), IDEFilter::Type(IDEType::GoLand)),
+      ("ruby".to_string(), IDEFilter::Type(IDEType::RubyMine)),
+      (
+        "studio".to_string(),
+        IDEFilter::Type(IDEType::AndroidStudio),
+      ),
+      (
+        "android".to_string(),
+        IDEFilter::Type(IDEType::AndroidStudio),
+      ),
+      ("rust".to_string(), IDEFilter::Type(IDEType::RustRover)),
+      ("iu".to_string(), IDEFilter::Product("IU".to_string())),
+      ("ic".to_string(), IDEFilter::Product("IC".to_string())),
+      ("pp".to_string(), IDEFilter::Product("PY".to_string())),
+      ("pc".to_string(), IDEFilter::Product("PC".to_string())),
     ];
 
-    let mut aliases = HashMap::<String, IDEType>::new();
-    aliases.extend(IDEType::iter().map(|v| v.get_default_alias()));
+    let mut aliases = HashMap::<String, IDEFilter>::new();
+    aliases.extend(IDEType::iter().map(|v| {
+      let (alias, ide_type) = v.get_default_alias();
+      (alias, IDEFilter::Type(ide_type))
+    }));
     aliases.extend(predefined_custom_aliases);
     aliases.extend(config.custom_aliases.iter().cloned());
 
-    debug!("Searching for installed IDEs...");
-    let glob = Glob::new(PRODUCT_INFO_GLOB_PATTERN)
-      .map_to_error_log("Failed to set up glob matcher for IDE product info")?;
-
-    let ides: Vec<_> = glob
-      .walk_with_behavior(&config.install_dir, LinkBehavior::ReadTarget)
-      .collect::<Result<Vec<_>, _>>()
-      .map_to_error_log("Failed to collect IDE paths")?
-      .into_par_iter()
-      .map(WalkEntry::into_path)
-      .filter_map(|entry| -> Option<Arc<IDEData>> {
-        debug!("Parsing IDE data from {:?} file", &entry);
-        let install_dir = entry.parent()?;
-        let product_info = IDEProductInfo::from_file(&entry).ok()?;
-
-        debug!(
-          "Looking for \"idea.properties\" file in {:?}...",
-          install_dir.join("bin/")
-        );
-        let config_path = IDEProperties::from_file(install_dir.join("bin/idea.properties"))
-          .and_then(|props| {
-            debug!("Properties file found, checking for config path setting...");
-
-            if props.config_path.is_none() {
-              debug!("Config path setting not found, using default...");
-            }
-
-            props.config_path
-          })
-          .unwrap_or_else(|| {
-            (if product_info.ide_type == IDEType::AndroidStudio {
-              "~/.config/Google"
-            } else {
-              "~/.config/JetBrains"
-            })
-            .resolve()
-            .to_path_buf()
-            .join(&product_info.data_directory_name)
-          });
-
-        debug!("Using {:?} as the config path.", &config_path);
-
-        Some(Arc::new(IDEData::from_product_info(
-          &product_info,
-          install_dir,
-          config_path,
-        )))
-      })
-      .collect();
-
-    debug!("Searching for recent projects...");
-    let projects: Vec<_> = ides
-      .par_iter()
-      .map(|ide| {
-        let glob = Glob::new(RECENT_PROJECTS_GLOB_PATTERN).map_to_error_log(format!(
-          "Failed to set up glob matcher for recent projects, {:?} is an invalid path",
-          &ide.config_path
-        ))?;
-        debug!(
-          "Looking for recent projects in {:?} directory...",
-          &ide.config_path
-        );
-
-        // Collect file paths first (can be parallelized)
-        let xml_files: Vec<_> = glob
-          .walk_with_behavior(&ide.config_path, LinkBehavior::ReadTarget)
-          .collect::<Result<Vec<_>, _>>()
-          .map_to_error_log("Failed to collect recent project files")?
-          .into_par_iter()
-          .map(WalkEntry::into_path)
-          .collect();
-
-        // Process XML files sequentially (due to NodePtr not being Send)
-        let ide_projects: Vec<RecentProject> = xml_files
-          .into_iter()
-          .filter_map(|entry| {
-            debug!("Reading recent projects XML file {entry:?}..");
-            RecentProjectsParser::from_file(entry, ide.clone()).ok()
-          })
-          .flatten()
-          .filter_map(|result| match result {
-            Ok(v) => Some(v),
-            Err(err) => {
-              warn!("{}", err);
-              None
-            }
-          })
-          .collect();
-
-        Ok::<Vec<RecentProject>, ()>(ide_projects)
-      })
-      .filter_map(|result| result.ok())
-      .flatten()
-      .collect();
-
-    let projects = projects
-      .into_par_iter()
-      .map(Arc::new)
-      .collect::<Vec<_>>()
-      .into_iter()
-      .sorted_by(|a, b| Ord::cmp(&b.last_opened, &a.last_opened))
-      .unique()
-      .collect::<Vec<_>>();
-
-    let entries = projects.par_iter().map(Arc::clone).collect::<Vec<_>>();
+    let loader = ProjectLoader::spawn(config.clone());
 
     Ok(Self {
       api,
       config,
-      projects,
-      entries,
+      loader,
+      projects: vec![],
+      entries: vec![],
       aliases,
       query: None,
+      missing_project: None,
+      notice: None,
     })
   }
 
   fn entries(&mut self) -> usize {
+    self.sync_projects();
+
+    if self.missing_project.is_some() {
+      return MISSING_PROJECT_ACTIONS.len();
+    }
+
     self.entries.len()
   }
 
   fn entry_content(&self, line: usize) -> rofi_mode::String {
-    self.entries[line].name.clone().into()
+    if self.missing_project.is_some() {
+      return MISSING_PROJECT_ACTIONS[line].into();
+    }
+
+    let project = &self.entries[line];
+
+    let name = match &project.group {
+      Some(group) => format!("{} [{}]", &project.name, &group.name),
+      None => project.name.clone(),
+    };
+
+    match project.status {
+      ProjectStatus::Available => name.into(),
+      ProjectStatus::Unavailable => format!("{} (unavailable)", name).into(),
+      ProjectStatus::Missing => format!("{} (missing)", name).into(),
+    }
   }
 
   fn entry_icon(&mut self, line: usize, size: u32) -> Option<Surface> {
+    if self.missing_project.is_some() {
+      return None;
+    }
+
     let project = &self.entries[line];
 
     if let Some(icon_path) = project
@@ -213,21 +157,26 @@ impl<'rofi> rofi_mode::Mode<'rofi> for Mode<'rofi> {
     }
 
     let ide = project.ide.clone();
-    let icon_name = ide.icon_name.replace("jetbrains-", "");
+    let icon_name = ide
+      .desktop_icon
+      .clone()
+      .unwrap_or_else(|| ide.icon_name.replace("jetbrains-", ""));
     let mut request_icon = |query: &str| -> Option<Surface> {
       wrap_icon_request!(self.api.query_icon(query, size).wait(&mut self.api))
     };
 
     debug!(
       "Requesting icon for {}, icon_name={}",
-      &ide.ide_type, &icon_name
+      ide.display_name(),
+      &icon_name
     );
     request_icon(&icon_name).or_else(|| {
       let fallback_path = project.ide.fallback_icon_path.to_string_lossy().to_string();
 
       debug!(
         "Requesting fallback icon for {}, fallback_path={}",
-        &ide.ide_type, &fallback_path
+        ide.display_name(),
+        &fallback_path
       );
       request_icon(&fallback_path)
     })
@@ -235,95 +184,380 @@ impl<'rofi> rofi_mode::Mode<'rofi> for Mode<'rofi> {
 
   fn react(&mut self, event: Event, input: &mut rofi_mode::String) -> Action {
     debug!("Received event {:?} with input {:?}", event, input);
+    self.notice = None;
+
+    if let Some(project) = self.missing_project.clone() {
+      return self.react_missing_project(project, event, input);
+    }
 
     match event {
       Event::Ok { selected, .. } => {
-        let project = &self.entries[selected];
+        let project = Arc::clone(&self.entries[selected]);
+
+        if project.status == ProjectStatus::Missing {
+          debug!(
+            "Offering clean up actions for missing {:?} project",
+            &project.path
+          );
+          self.missing_project = Some(project);
+          return Action::Reset;
+        }
+
+        if project.status == ProjectStatus::Unavailable {
+          let err = format!(
+            "Unable to open {:?} project, its path is not accessible",
+            &project.path
+          );
+          warn!("{}", err);
+          self.notice = Some(err);
+          return Action::Reload;
+        }
+
         let use_nix_devshell =
           project.ide.ide_type == IDEType::CLion && self.config.use_clion_devshell;
+        let mut args = project.ide.launch_command();
+        args.push(project.path.to_string_lossy().into_owned());
+
         let cmd = if use_nix_devshell {
-          "nix"
+          args.splice(0..0, ["develop".to_string(), "-c".to_string()]);
+          "nix".to_string()
         } else {
-          &project.ide.launcher_path.to_string_lossy()
-        };
-        let args = if use_nix_devshell {
-          vec![
-            "develop".to_string(),
-            "-c".to_string(),
-            project.ide.launcher_path.to_string_lossy().into_owned(),
-            project.path.to_string_lossy().into_owned(),
-          ]
-        } else {
-          vec![project.path.to_string_lossy().into_owned()]
+          args.remove(0)
         };
 
         let mut cmd = Command::new(cmd);
         cmd
           .args(args)
-          .current_dir(&project.path)
+          .current_dir(project.working_dir())
           .stdout(process::Stdio::null())
           .stderr(process::Stdio::null())
           .process_group(0);
 
         debug!("Executing command/s: {:?}", cmd);
 
-        #[allow(clippy::zombie_processes)]
-        cmd
-          .spawn()
-          .map_to_error_log(format!(
-            "Failed to spawn IDE with the project: {:?}",
-            &project.path
-          ))
-          .unwrap();
+        // Spawning enters the project's directory, which might sit on an unresponsive mount
+        let spawned = run_with_timeout(
+          project.working_dir(),
+          self.config.probe_timeout,
+          move || {
+            #[allow(clippy::zombie_processes)]
+            let child = cmd.spawn();
+            child.map(|_| ())
+          },
+        );
 
-        Action::Exit
+        let err = match spawned {
+          Some(Ok(_)) => return Action::Exit,
+          Some(Err(err)) => format!(
+            "Failed to spawn IDE with the {:?} project, reason: {}",
+            &project.path, err
+          ),
+          None => format!(
+            "Failed to spawn IDE with the {:?} project, its path is not responding",
+            &project.path
+          ),
+        };
+        warn!("{}", err);
+        self.notice = Some(err);
+        Action::Reload
       }
       Event::CustomInput { alt, .. } => {
         if self.query.is_none() || alt {
-          let query = input.split(' ').next().unwrap();
+          // Group names might contain spaces, so the whole input is used for them
+          let input = input.trim();
+          let query = match input.starts_with(GROUP_QUERY_PREFIX) {
+            true => input,
+            false => input.split(' ').next().unwrap(),
+          };
           debug!(
             "Attempting to set results into query-mode using {:?} as query..",
             query
           );
 
-          if let Some(ide) = self
-            .aliases
-            .get(query)
-            .cloned()
-            .or_else(|| IDEType::from_product_code(query))
-          {
-            self.query = Some(ide);
-            self.entries = self
+          let filter = match query.strip_prefix(GROUP_QUERY_PREFIX).map(str::trim_start) {
+            Some(group) => self
               .projects
               .iter()
-              .filter(|project| &project.ide.ide_type == self.query.as_ref().unwrap())
-              .map(Arc::clone)
-              .collect();
+              .any(|project| {
+                project
+                  .group
+                  .as_ref()
+                  .is_some_and(|project_group| project_group.name.eq_ignore_ascii_case(group))
+              })
+              .then(|| ProjectFilter::Group(group.to_owned())),
+            None => self
+              .aliases
+              .get(query)
+              .cloned()
+              .or_else(|| IDEFilter::from_product_code(query))
+              .or_else(|| {
+                self
+                  .projects
+                  .iter()
+                  .any(|project| project.ide.product_code == query)
+                  .then(|| IDEFilter::Product(query.to_owned()))
+              })
+              .map(ProjectFilter::IDE),
+          };
+
+          if let Some(filter) = filter {
+            self.query = Some(filter);
+            self.update_entries();
 
             debug!(
-              "Results set to query-mode, displaying results for IDE: {:?}",
+              "Results set to query-mode, displaying results for filter: {:?}",
               self.query.as_ref().unwrap()
             );
             Action::Reset
           } else {
-            debug!("Aborting change, no valid IDE found in the input");
+            debug!("Aborting change, no valid IDE or group found in the input");
             Action::Reload
           }
         } else {
           self.query = None;
-          self.entries = self.projects.iter().map(Arc::clone).collect();
+          self.update_entries();
 
           debug!("Results set into normal mode, requested by user");
           Action::Reload
         }
       }
+      Event::DeleteEntry { selected } => {
+        let project = Arc::clone(&self.entries[selected]);
+        if !self.ensure_loaded() {
+          return Action::Reload;
+        }
+
+        let project = self.merged_project(project);
+        if !self.ensure_ide_closed(&project) {
+          return Action::Reload;
+        }
+
+        if self.remove_origins(&project) {
+          debug!("Removed {:?} project", &project.path);
+          self.projects.retain(|other| **other != *project);
+          self.update_entries();
+        }
+
+        Action::Reload
+      }
       Event::Cancel { .. } => Action::Exit,
       _ => Action::Reload,
     }
   }
 
   fn matches(&self, line: usize, matcher: Matcher<'_>) -> bool {
+    // Input is used as the new location of a missing project, so actions are never filtered out
+    if self.missing_project.is_some() {
+      return true;
+    }
+
     // TODO: Better matching for user input
     matcher.matches(&self.entries[line].name)
   }
+
+  fn message(&mut self) -> rofi_mode::String {
+    if let Some(notice) = &self.notice {
+      markup_escape_text(notice).to_string().into()
+    } else if let Some(project) = &self.missing_project {
+      format!(
+        "<b>{}</b> no longer exists at <i>{}</i>, type a new location to relocate it",
+        markup_escape_text(&project.name),
+        markup_escape_text(&project.path.to_string_lossy())
+      )
+      .into()
+    } else if self.loader.is_loading() {
+      "<i>Loading recent projects...</i>".into()
+    } else {
+      rofi_mode::String::new()
+    }
+  }
+}
+
+impl Mode<'_> {
+  fn react_missing_project(
+    &mut self,
+    project: Arc<RecentProject>,
+    event: Event,
+    input: &mut rofi_mode::String,
+  ) -> Action {
+    let is_edit = matches!(event, Event::Ok { .. } | Event::CustomInput { .. });
+    if is_edit && !self.ensure_loaded() {
+      return Action::Reload;
+    }
+
+    let project = self.merged_project(project);
+    match event {
+      Event::Ok { .. } | Event::CustomInput { .. } if !self.ensure_ide_closed(&project) => {
+        return Action::Reload;
+      }
+      Event::Ok { selected: 0, .. } => {
+        if !self.remove_origins(&project) {
+          return Action::Reload;
+        }
+
+        debug!("Removed missing {:?} project", &project.path);
+        self.projects.retain(|other| **other != *project);
+      }
+      Event::Ok { .. } | Event::CustomInput { .. } => {
+        let input = input.trim();
+        if input.is_empty() {
+          return Action::Reload;
+        }
+
+        // The entered path might be on an unresponsive mount, which must not freeze rofi
+        let path = input.resolve().to_path_buf();
+        let probe = match PathProbe::run_with_timeout(&path, self.config.probe_timeout) {
+          Some(Ok(probe)) if !probe.missing => probe,
+          Some(Ok(_)) => {
+            warn!("Failed to relocate project, {:?} doesn't exist", &path);
+            return Action::Reload;
+          }
+          Some(Err(err)) => {
+            warn!("{}", err);
+            self.notice = Some(err);
+            return Action::Reload;
+          }
+          None => {
+            let err = format!(
+              "Failed to relocate project, probing of {:?} timed out",
+              &path
+            );
+            warn!("{}", err);
+            self.notice = Some(err);
+            return Action::Reload;
+          }
+        };
+
+        let raw_path = to_raw_path(&path);
+        let result = project
+          .origins
+          .iter()
+          .try_for_each(|origin| relocate_project(&origin.source, &origin.raw_path, &raw_path));
+        if let Err(err) = result {
+          warn!("{}", err);
+          self.notice = Some(err);
+          return Action::Reload;
+        }
+
+        debug!(
+          "Relocated missing {:?} project to {:?}",
+          &project.path, &path
+        );
+        let relocated = Arc::new(project.relocated(path, &raw_path, probe));
+
+        self.projects = self
+          .projects
+          .drain(..)
+          .map(|other| {
+            if *other == *project {
+              Arc::clone(&relocated)
+            } else {
+              other
+            }
+          })
+          .collect();
+      }
+      Event::Cancel { .. } => {}
+      _ => return Action::Reload,
+    }
+
+    self.missing_project = None;
+    self.update_entries();
+    Action::Reset
+  }
+
+  fn ensure_loaded(&mut self) -> bool {
+    // Files loaded later might list the same project, which would bring it back after the edit
+    if self.loader.is_loading() {
+      self.notice = Some(
+        "Wait until all recent projects are loaded, other IDEs might list the project too"
+          .to_owned(),
+      );
+      return false;
+    }
+
+    // Projects published right before the loader finished might not be merged yet
+    self.sync_projects();
+    true
+  }
+
+  fn merged_project(&self, project: Arc<RecentProject>) -> Arc<RecentProject> {
+    // Merging replaces the projects, the new ones know about all files listing the project
+    self
+      .projects
+      .iter()
+      .find(|other| other.as_ref() == project.as_ref())
+      .cloned()
+      .unwrap_or(project)
+  }
+
+  fn ensure_ide_closed(&mut self, project: &RecentProject) -> bool {
+    // Every IDE keeps its recent projects in memory and would overwrite the file on exit
+    let running = project
+      .origins
+      .iter()
+      .unique_by(|origin| &origin.ide.install_dir)
+      .find(|origin| is_ide_running(&origin.ide));
+
+    if let Some(origin) = running {
+      warn!(
+        "Refusing to edit {:?} file, the IDE is running",
+        &origin.source
+      );
+      self.notice = Some(format!(
+        "Close {} first, otherwise it will overwrite changes to its recent projects",
+        origin.ide.display_name()
+      ));
+      return false;
+    }
+
+    true
+  }
+
+  fn remove_origins(&mut self, project: &RecentProject) -> bool {
+    // The project has to be removed from every file listing it, otherwise it shows up again
+    let errors = project
+      .origins
+      .iter()
+      .filter_map(|origin| remove_project(&origin.source, &origin.raw_path).err())
+      .collect::<Vec<_>>();
+
+    if errors.is_empty() {
+      return true;
+    }
+
+    for err in &errors {
+      warn!("{}", err);
+    }
+    self.notice = Some(errors.join("\n"));
+    false
+  }
+
+  fn sync_projects(&mut self) {
+    let projects = self.loader.take_projects();
+    if projects.is_empty() {
+      return;
+    }
+
+    debug!(
+      "Received {} recent projects from the loader",
+      projects.len()
+    );
+    self.projects = merge_projects(self.projects.drain(..).chain(projects));
+    self.update_entries();
+  }
+
+  fn update_entries(&mut self) {
+    self.entries = self
+      .projects
+      .iter()
+      .filter(|project| {
+        self
+          .query
+          .as_ref()
+          .is_none_or(|query| query.matches(project))
+      })
+      .map(Arc::clone)
+      .collect();
+  }
 }
@@ -0,0 +1,205 @@
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+
+use glib::{debug, warn, MainContext};
+use itertools::Itertools;
+use rayon::prelude::*;
+use wax::{Glob, LinkBehavior, WalkEntry};
+
+use crate::cache::IndexCache;
+use crate::config::Config;
+use crate::ide::discovery::{discover_ides, discovery_sources};
+use crate::ide::version::resolve_preferred_ides;
+use crate::recent_project::path_macros::PathMacros;
+use crate::recent_project::{ProjectStatus, RecentProject, RecentProjectEntry};
+use crate::traits::MapToErrorLog;
+use crate::G_LOG_DOMAIN;
+
+static RECENT_PROJECTS_GLOB_PATTERN: &str = "options/{recentProjects,recentSolutions}.xml";
+static LOADER_THREAD_NAME: &str = "jetbrains-loader";
+
+#[derive(Debug)]
+pub struct ProjectLoader {
+  state: Arc<Mutex<LoaderState>>,
+}
+
+#[derive(Debug, Default)]
+struct LoaderState {
+  projects: Vec<Arc<RecentProject>>,
+  loading: bool,
+}
+
+impl ProjectLoader {
+  pub fn spawn(config: Config) -> Self {
+    let state = Arc::new(Mutex::new(LoaderState {
+      loading: true,
+      ..Default::default()
+    }));
+    let worker_state = Arc::clone(&state);
+
+    let result = thread::Builder::new()
+      .name(LOADER_THREAD_NAME.to_owned())
+      .spawn(move || {
+        load_projects(&config, |projects| {
+          lock_state(&worker_state).projects.extend(projects);
+          request_reload();
+        });
+
+        debug!("Finished loading recent projects");
+        lock_state(&worker_state).loading = false;
+        request_reload();
+      })
+      .map_to_error_log("Failed to spawn the recent projects loader thread");
+
+    if result.is_err() {
+      lock_state(&state).loading = false;
+    }
+
+    Self { state }
+  }
+
+  pub fn is_loading(&self) -> bool {
+    lock_state(&self.state).loading
+  }
+
+  pub fn take_projects(&self) -> Vec<Arc<RecentProject>> {
+    std::mem::take(&mut lock_state(&self.state).projects)
+  }
+}
+
+fn lock_state(state: &Mutex<LoaderState>) -> MutexGuard<'_, LoaderState> {
+  state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn request_reload() {
+  // Rofi is not thread-safe, so the reload has to be requested from its main loop
+  MainContext::default().invoke(|| unsafe { rofi_mode::ffi::view::reload() });
+}
+
+fn load_projects<F>(config: &Config, publish: F)
+where
+  F: Fn(Vec<Arc<RecentProject>>) + Sync,
+{
+  let mut cache = IndexCache::load(config);
+
+  debug!("Searching for installed IDEs...");
+  let discovery_sources = discovery_sources(config);
+  let ides = match cache.ides(&discovery_sources) {
+    Some(ides) => {
+      debug!("Using {} cached IDEs", ides.len());
+      ides
+    }
+    None => {
+      let ides = discover_ides(config);
+      cache.set_ides(discovery_sources, &ides);
+      ides
+    }
+  };
+
+  debug!("Resolving IDE versions used for launching projects...");
+  let preferred_ides = resolve_preferred_ides(&ides, &config.version_preferences);
+  for ide in preferred_ides.values() {
+    debug!(
+      "Using {} {} (build {}) to open {:?} projects",
+      ide.display_name(),
+      &ide.version,
+      &ide.build_number,
+      &ide.product_code
+    );
+  }
+
+  debug!("Searching for recent projects...");
+  let projects_by_file: Vec<_> = ides
+    .par_iter()
+    .map(|ide| {
+      let glob = Glob::new(RECENT_PROJECTS_GLOB_PATTERN).map_to_error_log(format!(
+        "Failed to set up glob matcher for recent projects, {:?} is an invalid path",
+        &ide.config_path
+      ))?;
+      debug!(
+        "Looking for recent projects in {:?} directory...",
+        &ide.config_path
+      );
+
+      let xml_files: Vec<_> = glob
+        .walk_with_behavior(&ide.config_path, LinkBehavior::ReadTarget)
+        .collect::<Result<Vec<_>, _>>()
+        .map_to_error_log("Failed to collect recent project files")?
+        .into_par_iter()
+        .map(WalkEntry::into_path)
+        .collect();
+
+      let path_macros = PathMacros::load(ide);
+      let ide_projects: Vec<_> = xml_files
+        .into_par_iter()
+        .filter_map(|entry| {
+          let projects = match cache.projects(&entry, ide) {
+            Some(projects) => {
+              debug!("Using cached recent projects from {entry:?} file");
+              projects
+            }
+            None => {
+              debug!("Reading recent projects XML file {entry:?}..");
+              RecentProjectEntry::from_file(&entry)
+                .ok()?
+                .into_par_iter()
+                .filter_map(|project| {
+                  match RecentProject::from_entry(project, ide.clone(), &path_macros, config) {
+                    Ok(v) => Some(v),
+                    Err(err) => {
+                      warn!("{}", err);
+                      None
+                    }
+                  }
+                })
+                .collect::<Vec<_>>()
+            }
+          };
+
+          // Feed the projects as soon as they are resolved, so they show up without waiting for others
+          publish(
+            projects
+              .iter()
+              .cloned()
+              .map(|mut project| {
+                if let Some(ide) = preferred_ides.get(&project.ide.product_code) {
+                  project.ide = Arc::clone(ide);
+                }
+
+                Arc::new(project)
+              })
+              .collect(),
+          );
+
+          Some((entry, projects))
+        })
+        .collect();
+
+      Ok::<Vec<_>, ()>(ide_projects)
+    })
+    .filter_map(|result| result.ok())
+    .flatten()
+    .collect();
+
+  debug!(
+    "Found {} recent projects",
+    projects_by_file
+      .iter()
+      .flat_map(|(_, projects)| projects)
+      .unique()
+      .count()
+  );
+
+  // Files with unavailable or missing projects aren't cached, so they are probed again on the next start
+  cache.set_projects(
+    projects_by_file
+      .into_iter()
+      .filter(|(_, projects)| {
+        projects
+          .iter()
+          .all(|project| project.status == ProjectStatus::Available)
+      })
+      .collect(),
+  );
+  cache.save();
+}
@@ -1,14 +1,3 @@
-macro_rules! ensure_option {
-  ($code:expr, $($arg:tt)+) => {
-    match $code {
-      Some(v) => v,
-      None => {
-        return Some(Err(format!($($arg)+)))
-      }
-    }
-  };
-}
-
 macro_rules! ensure_result {
   ($code:expr, $($arg:tt)+) => {
     match $code {
@@ -33,6 +22,5 @@ macro_rules! wrap_icon_request {
   };
 }
 
-pub(crate) use ensure_option;
 pub(crate) use ensure_result;
 pub(crate) use wrap_icon_request;
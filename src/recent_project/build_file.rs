@@ -0,0 +1,130 @@
+use std::fs::read_to_string;
+use std::path::Path;
+
+use glib::warn;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+
+use crate::G_LOG_DOMAIN;
+
+static GRADLE_SETTINGS_FILES: [&str; 2] = ["settings.gradle.kts", "settings.gradle"];
+static GRADLE_ROOT_PROJECT_NAME: &str = "rootProject.name";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BuildFile {
+  Maven,
+  Gradle,
+  Cargo,
+}
+
+impl BuildFile {
+  pub fn from_path(path: &Path) -> Option<Self> {
+    match path.file_name()?.to_str()? {
+      "pom.xml" => Some(BuildFile::Maven),
+      "build.gradle" | "build.gradle.kts" => Some(BuildFile::Gradle),
+      "Cargo.toml" => Some(BuildFile::Cargo),
+      _ => None,
+    }
+  }
+
+  pub fn read_name(&self, path: &Path) -> Option<String> {
+    let name = match self {
+      BuildFile::Maven => read_maven_artifact_id(path),
+      BuildFile::Gradle => {
+        // Gradle keeps the project's name in the settings file next to the build file
+        let dir = path.parent()?;
+        GRADLE_SETTINGS_FILES
+          .iter()
+          .find_map(|name| read_gradle_root_project_name(&dir.join(name)))
+      }
+      BuildFile::Cargo => read_cargo_package_name(path),
+    };
+
+    name.filter(|name| !name.is_empty())
+  }
+}
+
+fn read_maven_artifact_id(path: &Path) -> Option<String> {
+  let xml = read_to_string(path).ok()?;
+  let mut reader = Reader::from_str(&xml);
+  reader.config_mut().trim_text(true);
+  let mut stack = Vec::<String>::new();
+
+  loop {
+    match reader.read_event() {
+      Ok(Event::Start(element)) => {
+        stack.push(String::from_utf8_lossy(element.local_name().as_ref()).to_string());
+      }
+      Ok(Event::End(_)) => {
+        stack.pop();
+      }
+      // Only the project's own artifactId counts, not the ones of its parent or dependencies
+      Ok(Event::Text(text)) if stack == ["project", "artifactId"] => {
+        return text.unescape().ok().map(|value| value.trim().to_owned());
+      }
+      Ok(Event::Eof) => return None,
+      Ok(_) => {}
+      Err(err) => {
+        warn!("Failed to parse {:?} file, reason: {}", path, err);
+        return None;
+      }
+    }
+  }
+}
+
+fn read_gradle_root_project_name(path: &Path) -> Option<String> {
+  let script = read_to_string(path).ok()?;
+
+  script.lines().find_map(|line| {
+    let value = line
+      .trim()
+      .strip_prefix(GRADLE_ROOT_PROJECT_NAME)?
+      .trim_start()
+      .strip_prefix('=')?;
+
+    Some(unquote(value))
+  })
+}
+
+fn read_cargo_package_name(path: &Path) -> Option<String> {
+  let manifest = read_to_string(path).ok()?;
+  let mut in_package = false;
+
+  // A workspace manifest has no package section, so it's left to the directory name
+  for line in manifest.lines().map(str::trim) {
+    if line.starts_with('[') {
+      in_package = line == "[package]";
+      continue;
+    }
+
+    if !in_package {
+      continue;
+    }
+
+    let Some((key, value)) = line.split_once('=') else {
+      continue;
+    };
+
+    if key.trim() == "name" {
+      return Some(unquote(value));
+    }
+  }
+
+  None
+}
+
+fn unquote(value: &str) -> String {
+  // Take only the content of the string literal, dropping anything after it (e.g. comments)
+  let value = value.trim();
+  let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'');
+
+  match quote {
+    Some(quote) => value[1..]
+      .split(quote)
+      .next()
+      .unwrap_or_default()
+      .to_owned(),
+    None => value.to_owned(),
+  }
+}
@@ -0,0 +1,323 @@
+use std::fs::{copy, read_to_string, rename, write};
+use std::path::Path;
+
+use glib::debug;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, Writer};
+
+use crate::recent_project::{
+  element_segment, entry_path_attribute, is_entry_path, is_manager_component, read_element,
+};
+use crate::G_LOG_DOMAIN;
+
+#[derive(Debug, Clone, Copy)]
+enum EntryEdit<'a> {
+  Remove,
+  Relocate(&'a str),
+}
+
+pub fn remove_project<T: AsRef<Path>>(source: T, raw_path: &str) -> Result<(), String> {
+  edit_entries(source.as_ref(), raw_path, EntryEdit::Remove)
+}
+
+pub fn relocate_project<T: AsRef<Path>>(
+  source: T,
+  raw_path: &str,
+  new_raw_path: &str,
+) -> Result<(), String> {
+  edit_entries(source.as_ref(), raw_path, EntryEdit::Relocate(new_raw_path))
+}
+
+pub fn to_raw_path<T: AsRef<Path>>(path: T) -> String {
+  let path = path.as_ref();
+
+  // IDEs store paths inside the home directory using the "$USER_HOME$" macro
+  match dirs::home_dir().and_then(|home| path.strip_prefix(home).ok()) {
+    Some(relative_path) if relative_path.as_os_str().is_empty() => "$USER_HOME$".to_owned(),
+    Some(relative_path) => format!("$USER_HOME$/{}", relative_path.to_string_lossy()),
+    None => path.to_string_lossy().to_string(),
+  }
+}
+
+fn edit_entries(source: &Path, raw_path: &str, edit: EntryEdit) -> Result<(), String> {
+  debug!(
+    "Applying {:?} to {:?} entries in {:?}",
+    edit, raw_path, source
+  );
+  let xml = read_to_string(source)
+    .map_err(|err| format!("Failed to read {:?} file, reason: {}", source, err))?;
+  let parse_error = |err| format!("Failed to parse {:?} file, reason: {}", source, err);
+
+  let mut reader = Reader::from_str(&xml);
+  let mut writer = Writer::new(Vec::<u8>::new());
+
+  let mut stack = Vec::<String>::new();
+  let mut component_depth: Option<usize> = None;
+  let mut skip_depth: Option<usize> = None;
+  let mut edited = 0;
+
+  loop {
+    let event = reader.read_event().map_err(parse_error)?;
+    if let Event::Eof = event {
+      break;
+    }
+
+    // Drop everything inside of a removed entry
+    if let Some(depth) = skip_depth {
+      match event {
+        Event::Start(_) => stack.push(String::new()),
+        Event::End(_) => {
+          stack.pop();
+          if stack.len() < depth {
+            skip_depth = None;
+          }
+        }
+        _ => {}
+      }
+      continue;
+    }
+
+    let mut replacement = None;
+    match &event {
+      Event::Start(element) | Event::Empty(element) => {
+        let is_empty = matches!(event, Event::Empty(_));
+        let (tag, attributes) = read_element(element);
+        stack.push(element_segment(&tag, &attributes));
+        let depth = stack.len();
+
+        if component_depth.is_none() && is_manager_component(&tag, &attributes) {
+          component_depth = Some(depth);
+        } else if let Some(component_depth) = component_depth {
+          let matched_key = entry_path_attribute(&attributes)
+            .filter(|(_, path)| *path == raw_path && is_entry_path(&stack[component_depth..]))
+            .map(|(key, _)| key);
+
+          if let Some(key) = matched_key {
+            edited += 1;
+
+            match edit {
+              EntryEdit::Remove if is_empty => {
+                stack.pop();
+                continue;
+              }
+              EntryEdit::Remove => {
+                skip_depth = Some(depth);
+                continue;
+              }
+              EntryEdit::Relocate(new_raw_path) => {
+                let mut relocated = BytesStart::new(tag.clone());
+                for attribute in element.attributes().flatten() {
+                  if attribute.key.as_ref() == key.as_bytes() {
+                    relocated.push_attribute((key, new_raw_path));
+                  } else {
+                    relocated.push_attribute(attribute);
+                  }
+                }
+
+                replacement = Some(if is_empty {
+                  Event::Empty(relocated)
+                } else {
+                  Event::Start(relocated)
+                });
+              }
+            }
+          }
+        }
+
+        if is_empty {
+          stack.pop();
+        }
+      }
+      Event::End(_) => {
+        stack.pop();
+      }
+      _ => {}
+    }
+
+    if component_depth.is_some_and(|depth| depth > stack.len()) {
+      component_depth = None;
+    }
+
+    writer
+      .write_event(replacement.unwrap_or(event))
+      .map_err(|err| format!("Failed to write {:?} file, reason: {}", source, err))?;
+  }
+
+  if edited == 0 {
+    return Err(format!(
+      "Failed to edit {:?} file, no entries found for {:?}",
+      source, raw_path
+    ));
+  }
+
+  // Keep a copy of the original file, in case the edit has to be reverted manually
+  let backup_path = source.with_extension("xml.bak");
+  copy(source, &backup_path)
+    .map_err(|err| format!("Failed to back up {:?} file, reason: {}", source, err))?;
+  debug!("Backed up {:?} to {:?}", source, &backup_path);
+
+  // Write into a temporary file first, so the IDE never reads a partially written file
+  let temp_path = source.with_extension("xml.tmp");
+  write(&temp_path, writer.into_inner())
+    .and_then(|_| rename(&temp_path, source))
+    .map_err(|err| format!("Failed to write {:?} file, reason: {}", source, err))?;
+
+  debug!("Updated {} entries in {:?}", edited, source);
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs::{create_dir_all, read_to_string, remove_dir_all, write};
+  use std::path::PathBuf;
+  use std::process;
+
+  use super::*;
+  use crate::recent_project::RecentProjectEntry;
+
+  static RECENT_PROJECTS_XML: &str = r#"<application>
+  <component name="RecentProjectsManager">
+    <option name="additionalInfo">
+      <map>
+        <entry key="$USER_HOME$/alpha">
+          <value>
+            <RecentProjectMetaInfo projectWorkspaceId="alpha-id">
+              <option name="projectOpenTimestamp" value="1700000000000" />
+            </RecentProjectMetaInfo>
+          </value>
+        </entry>
+        <entry key="$USER_HOME$/beta" />
+      </map>
+    </option>
+    <option name="groups">
+      <list>
+        <ProjectGroup>
+          <option name="name" value="work" />
+          <option name="projects">
+            <list>
+              <option value="$USER_HOME$/alpha" />
+              <option value="$USER_HOME$/beta"></option>
+            </list>
+          </option>
+        </ProjectGroup>
+      </list>
+    </option>
+    <option name="recentPaths">
+      <list>
+        <option value="$USER_HOME$/alpha"></option>
+        <option value="$USER_HOME$/beta" />
+      </list>
+    </option>
+  </component>
+  <component name="OtherComponent">
+    <option value="$USER_HOME$/alpha" />
+  </component>
+</application>
+"#;
+
+  fn write_fixture(name: &str) -> PathBuf {
+    let dir =
+      std::env::temp_dir().join(format!("rofi-jetbrains-editor-{}-{}", process::id(), name));
+    let _ = remove_dir_all(&dir);
+    create_dir_all(&dir).unwrap();
+
+    let path = dir.join("recentProjects.xml");
+    write(&path, RECENT_PROJECTS_XML).unwrap();
+    path
+  }
+
+  fn read_entries(path: &Path) -> Vec<(String, Option<String>)> {
+    RecentProjectEntry::from_file(path)
+      .unwrap()
+      .into_iter()
+      .map(|entry| (entry.path, entry.group.map(|group| group.name)))
+      .collect()
+  }
+
+  #[test]
+  fn removes_all_entries_of_a_project() {
+    let path = write_fixture("remove");
+
+    // "alpha" has a non-empty "additionalInfo" entry and "recentPaths" option, but an empty group option
+    remove_project(&path, "$USER_HOME$/alpha").unwrap();
+    let xml = read_to_string(&path).unwrap();
+    assert!(!xml.contains("alpha-id"));
+    assert!(!xml.contains("RecentProjectMetaInfo"));
+    assert_eq!(xml.matches("$USER_HOME$/alpha").count(), 1);
+    assert!(xml.contains("<component name=\"OtherComponent\">"));
+    assert_eq!(
+      read_entries(&path),
+      vec![("$USER_HOME$/beta".to_owned(), Some("work".to_owned()))]
+    );
+
+    // "beta" has an empty "additionalInfo" entry and "recentPaths" option, but a non-empty group option
+    remove_project(&path, "$USER_HOME$/beta").unwrap();
+    let xml = read_to_string(&path).unwrap();
+    assert!(!xml.contains("$USER_HOME$/beta"));
+    assert!(xml.contains("<option name=\"name\" value=\"work\""));
+    assert!(read_entries(&path).is_empty());
+
+    let backup = read_to_string(path.with_extension("xml.bak")).unwrap();
+    assert!(backup.contains("$USER_HOME$/beta"));
+    assert!(!path.with_extension("xml.tmp").exists());
+
+    let _ = remove_dir_all(path.parent().unwrap());
+  }
+
+  #[test]
+  fn relocates_all_entries_of_a_project() {
+    let path = write_fixture("relocate");
+
+    relocate_project(&path, "$USER_HOME$/alpha", "$USER_HOME$/gamma").unwrap();
+    relocate_project(&path, "$USER_HOME$/beta", "/opt/delta").unwrap();
+    let xml = read_to_string(&path).unwrap();
+    assert_eq!(xml.matches("$USER_HOME$/gamma").count(), 3);
+    assert_eq!(xml.matches("/opt/delta").count(), 3);
+    assert!(xml.contains("alpha-id"));
+    assert!(xml.contains("<entry key=\"/opt/delta\"/>"));
+    assert!(xml.contains("<option value=\"/opt/delta\"></option>"));
+
+    // Entries outside of the recent projects components are left as they are
+    assert_eq!(xml.matches("$USER_HOME$/alpha").count(), 1);
+
+    let entries = read_entries(&path);
+    assert_eq!(
+      entries,
+      vec![
+        ("$USER_HOME$/gamma".to_owned(), Some("work".to_owned())),
+        ("/opt/delta".to_owned(), Some("work".to_owned())),
+      ]
+    );
+    let meta = RecentProjectEntry::from_file(&path).unwrap().remove(0).meta;
+    assert_eq!(
+      meta.get("projectWorkspaceId").map(String::as_str),
+      Some("alpha-id")
+    );
+
+    let _ = remove_dir_all(path.parent().unwrap());
+  }
+
+  #[test]
+  fn fails_without_matching_entries() {
+    let path = write_fixture("unknown");
+
+    assert!(remove_project(&path, "$USER_HOME$/unknown").is_err());
+    assert!(relocate_project(&path, "$USER_HOME$/unknown", "/opt/other").is_err());
+    assert_eq!(read_to_string(&path).unwrap(), RECENT_PROJECTS_XML);
+    assert!(!path.with_extension("xml.bak").exists());
+
+    let _ = remove_dir_all(path.parent().unwrap());
+  }
+
+  #[test]
+  fn converts_home_paths_to_raw_paths() {
+    let home = dirs::home_dir().unwrap();
+
+    assert_eq!(to_raw_path(&home), "$USER_HOME$");
+    assert_eq!(
+      to_raw_path(home.join("projects/app")),
+      "$USER_HOME$/projects/app"
+    );
+    assert_eq!(to_raw_path("/opt/app"), "/opt/app");
+  }
+}
@@ -0,0 +1,812 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::{metadata, read_to_string};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard, OnceLock, PoisonError};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Local};
+use glib::{debug, warn};
+use itertools::Itertools;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use wax::{Glob, LinkBehavior, WalkEntry};
+
+use crate::config::Config;
+use crate::ide::data::IDEData;
+use crate::ide::IDEFilter;
+use crate::macros::ensure_result;
+use crate::recent_project::build_file::BuildFile;
+use crate::recent_project::path_macros::PathMacros;
+use crate::traits::MapToErrorLog;
+use crate::G_LOG_DOMAIN;
+
+pub mod build_file;
+pub mod editor;
+pub mod path_macros;
+
+static MANAGER_COMPONENTS: [&str; 4] = [
+  "RecentProjectsManager",
+  "RecentDirectoryProjectsManager",
+  "RiderRecentProjectsManager",
+  "RiderRecentDirectoryProjectsManager",
+];
+static ENTRY_PATHS: [&str; 3] = [
+  "option[@name=\"recentPaths\"]/list/option",
+  "option[@name=\"additionalInfo\"]/map/entry",
+  "option[@name=\"groups\"]/list/ProjectGroup/option[@name=\"projects\"]/list/option",
+];
+static META_INFO_PATH: &str = "value/RecentProjectMetaInfo";
+static GROUP_PATH: &str = "option[@name=\"groups\"]/list/ProjectGroup";
+
+pub static PROJECT_SETTINGS_DIR: &str = ".idea";
+static WORKSPACE_FILE_PATH: &str = ".idea/workspace.xml";
+static UNITY_PROJECT_DIRS: [&str; 2] = ["Assets", "ProjectSettings"];
+static PROBE_THREAD_NAME: &str = "jetbrains-probe";
+static MOUNT_INFO_PATH: &str = "/proc/self/mountinfo";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentProject {
+  pub name: String,
+  pub path: PathBuf,
+  pub icon: Option<PathBuf>,
+  pub ide: Arc<IDEData>,
+  pub last_opened: DateTime<Local>,
+  pub meta: RecentProjectMeta,
+  pub group: Option<ProjectGroup>,
+  pub status: ProjectStatus,
+  #[serde(default)]
+  pub kind: ProjectKind,
+  pub origins: Vec<ProjectOrigin>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectOrigin {
+  pub source: PathBuf,
+  pub raw_path: String,
+  pub ide: Arc<IDEData>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecentProjectMeta {
+  pub display_name: Option<String>,
+  pub frame_title: Option<String>,
+  pub opened: bool,
+  pub build: Option<String>,
+  pub production_code: Option<String>,
+  pub bin_folder: Option<String>,
+  pub project_workspace_id: Option<String>,
+  pub open_timestamp: Option<DateTime<Local>>,
+  pub activation_timestamp: Option<DateTime<Local>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectGroup {
+  pub name: String,
+  pub expanded: bool,
+  pub tutorials: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum ProjectFilter {
+  IDE(IDEFilter),
+  Group(String),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProjectStatus {
+  #[default]
+  Available,
+  Unavailable,
+  Missing,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProjectKind {
+  #[default]
+  Directory,
+  File,
+  Solution(SolutionFormat),
+  Unity(Option<SolutionFormat>),
+  Ipr,
+  BuildFile(BuildFile),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SolutionFormat {
+  Sln,
+  Slnx,
+  Slnf,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RecentProjectEntry {
+  pub source: PathBuf,
+  pub source_modified: Option<SystemTime>,
+  pub path: String,
+  pub meta: HashMap<String, String>,
+  pub group: Option<ProjectGroup>,
+}
+
+#[derive(Debug, Default)]
+pub struct PathProbe {
+  pub missing: bool,
+  pub is_file: bool,
+  pub is_unity: bool,
+  pub build_file_name: Option<String>,
+  pub workspace_modified: Option<SystemTime>,
+  pub name: Option<String>,
+  pub icon: Option<PathBuf>,
+}
+
+impl PartialEq for RecentProject {
+  fn eq(&self, other: &Self) -> bool {
+    // We can ignore "name", "icon" as it should always be evaluated to the same value given the same project path
+    // Also don't compare the "last_opened" prop as it might vary between entries for the same project
+    // Instead let's update the value to the most recent timestamp
+    self.path == other.path && self.ide.product_code == other.ide.product_code
+  }
+}
+
+impl Eq for RecentProject {}
+
+impl Hash for RecentProject {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    // See notes for the "PartialEq" implementation to read why only these props are hashed
+    Hash::hash(&self.path, state);
+    Hash::hash(&self.ide.product_code, state);
+  }
+}
+
+impl RecentProjectEntry {
+  pub fn from_file<T: AsRef<Path>>(path: T) -> Result<Vec<RecentProjectEntry>, ()> {
+    let path = path.as_ref();
+    let xml =
+      read_to_string(path).map_to_error_log("Failed to read the recent projects XML file")?;
+    let entries =
+      Self::from_xml(&xml).map_to_error_log("Failed to parse recent projects XML file")?;
+    let source_modified = modified_time(path);
+
+    Ok(
+      entries
+        .into_iter()
+        .map(|entry| RecentProjectEntry {
+          source: path.to_path_buf(),
+          source_modified,
+          ..entry
+        })
+        .collect(),
+    )
+  }
+
+  fn from_xml(xml: &str) -> Result<Vec<RecentProjectEntry>, quick_xml::Error> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    // Element paths are tracked as "tag[@name=...]" segments, so they can be matched against static paths
+    let mut stack = Vec::<String>::new();
+    let mut component_depth: Option<usize> = None;
+    let mut current: Option<(usize, RecentProjectEntry)> = None;
+    let mut current_group: Option<(usize, ProjectGroup, Vec<usize>)> = None;
+    let mut entries = vec![];
+
+    loop {
+      let event = reader.read_event()?;
+
+      if let Event::Start(element) | Event::Empty(element) = &event {
+        let (tag, attributes) = read_element(element);
+        stack.push(element_segment(&tag, &attributes));
+        let depth = stack.len();
+
+        match (component_depth, &mut current) {
+          (None, _) => {
+            if is_manager_component(&tag, &attributes) {
+              component_depth = Some(depth);
+            }
+          }
+          (Some(component_depth), None) => {
+            if stack[component_depth..].join("/") == GROUP_PATH {
+              current_group = Some((depth, ProjectGroup::default(), vec![]));
+            } else if let Some((group_depth, group, _)) = &mut current_group {
+              // Group settings are stored as direct "option" children of the group
+              if depth == *group_depth + 1 && tag == "option" {
+                let value = attributes.get("value").cloned().unwrap_or_default();
+
+                match attributes.get("name").map(String::as_str) {
+                  Some("name") => group.name = value,
+                  Some("expanded") => group.expanded = value == "true",
+                  Some("tutorials") => group.tutorials = value == "true",
+                  _ => {}
+                }
+              }
+            }
+
+            if is_entry_path(&stack[component_depth..]) {
+              if let Some((_, path)) = entry_path_attribute(&attributes) {
+                current = Some((
+                  depth,
+                  RecentProjectEntry {
+                    path: path.clone(),
+                    ..Default::default()
+                  },
+                ));
+              }
+            }
+          }
+          (Some(_), Some((entry_depth, entry))) => {
+            let relative_path = stack[*entry_depth..].join("/");
+            let parent_path = stack[*entry_depth..depth - 1].join("/");
+
+            if relative_path == META_INFO_PATH {
+              entry.meta.extend(attributes);
+            } else if parent_path == META_INFO_PATH && tag == "option" {
+              if let (Some(name), Some(value)) = (attributes.get("name"), attributes.get("value")) {
+                entry.meta.insert(name.clone(), value.clone());
+              }
+            }
+          }
+        }
+      }
+
+      if let Event::End(_) | Event::Empty(_) = &event {
+        stack.pop();
+
+        if current
+          .as_ref()
+          .is_some_and(|(depth, _)| *depth > stack.len())
+        {
+          if let Some((_, _, indices)) = &mut current_group {
+            indices.push(entries.len());
+          }
+          entries.extend(current.take().map(|(_, entry)| entry));
+        }
+        if current_group
+          .as_ref()
+          .is_some_and(|(depth, _, _)| *depth > stack.len())
+        {
+          if let Some((_, group, indices)) = current_group.take() {
+            for index in indices {
+              entries[index].group = Some(group.clone());
+            }
+          }
+        }
+        if component_depth.is_some_and(|depth| depth > stack.len()) {
+          component_depth = None;
+        }
+      }
+
+      if let Event::Eof = event {
+        break;
+      }
+    }
+
+    // The same project is usually listed in multiple places, so merge its metadata into one entry
+    let mut merged = Vec::<RecentProjectEntry>::with_capacity(entries.len());
+    let mut indices = HashMap::<String, usize>::new();
+    for entry in entries {
+      match indices.get(&entry.path) {
+        Some(&index) => {
+          for (key, value) in entry.meta {
+            merged[index].meta.entry(key).or_insert(value);
+          }
+          if merged[index].group.is_none() {
+            merged[index].group = entry.group;
+          }
+        }
+        None => {
+          indices.insert(entry.path.clone(), merged.len());
+          merged.push(entry);
+        }
+      }
+    }
+
+    Ok(merged)
+  }
+}
+
+impl RecentProject {
+  pub fn from_entry(
+    entry: RecentProjectEntry,
+    ide: Arc<IDEData>,
+    path_macros: &PathMacros,
+    config: &Config,
+  ) -> Result<Self, String> {
+    let path = path_macros.expand(&entry.path, None);
+
+    // Probe the project's path, without letting stale mounts block the launcher
+    let (probe, status) = if config
+      .unprobed_paths
+      .iter()
+      .any(|prefix| path.starts_with(prefix))
+    {
+      debug!(
+        "Skipping probing of {:?} project, the path is excluded",
+        &path
+      );
+      (PathProbe::default(), ProjectStatus::Available)
+    } else {
+      match PathProbe::run_with_timeout(&path, config.probe_timeout) {
+        Some(probe) => {
+          let probe = probe?;
+
+          match probe.missing {
+            false => (probe, ProjectStatus::Available),
+            true if config.show_missing_projects => (probe, ProjectStatus::Missing),
+            true => {
+              return Err(format!(
+                "Ignoring {:?} project, the path doesn't exists",
+                &path
+              ))
+            }
+          }
+        }
+        None => {
+          warn!(
+            "Probing of {:?} project timed out, marking it as unavailable",
+            &path
+          );
+          (PathProbe::default(), ProjectStatus::Unavailable)
+        }
+      }
+    };
+
+    let mut meta = RecentProjectMeta::from_map(&entry.meta);
+    meta.bin_folder = meta.bin_folder.map(|raw| {
+      path_macros
+        .expand(&raw, Some(&path))
+        .to_string_lossy()
+        .to_string()
+    });
+
+    // Extract project's last opened timestamp, falling back to the best value available
+    let last_opened = meta
+      .open_timestamp
+      .or(meta.activation_timestamp)
+      .or_else(|| probe.workspace_modified.map(DateTime::from))
+      .or_else(|| entry.source_modified.map(DateTime::from))
+      .unwrap_or_else(|| DateTime::from(SystemTime::UNIX_EPOCH));
+
+    let kind = ProjectKind::detect(&path, probe.is_file, probe.is_unity);
+
+    // Resolve project's name, preferring the one set in the IDE (or the .sln file for solutions)
+    let name = ensure_result!(
+      meta
+        .display_name
+        .clone()
+        .or(probe.name)
+        .or(probe.build_file_name)
+        .or_else(|| kind.fallback_name(&path)),
+      "Failed to resolve project name of {:?} project",
+      &entry.path
+    );
+
+    let origin = ProjectOrigin {
+      source: entry.source,
+      raw_path: entry.path,
+      ide: Arc::clone(&ide),
+    };
+    Ok(RecentProject {
+      name,
+      path,
+      icon: probe.icon,
+      ide,
+      last_opened,
+      meta,
+      group: entry.group,
+      status,
+      kind,
+      origins: vec![origin],
+    })
+  }
+}
+
+impl RecentProject {
+  pub fn relocated(&self, path: PathBuf, raw_path: &str, probe: PathProbe) -> Self {
+    let kind = ProjectKind::detect(&path, probe.is_file, probe.is_unity);
+    let name = probe
+      .name
+      .or(probe.build_file_name)
+      .or_else(|| kind.fallback_name(&path))
+      .unwrap_or_else(|| self.name.clone());
+    let origins = self
+      .origins
+      .iter()
+      .map(|origin| ProjectOrigin {
+        raw_path: raw_path.to_owned(),
+        ..origin.clone()
+      })
+      .collect();
+
+    RecentProject {
+      name,
+      path,
+      icon: probe.icon,
+      status: ProjectStatus::Available,
+      kind,
+      origins,
+      ..self.clone()
+    }
+  }
+
+  fn merge(&mut self, other: &RecentProject) {
+    self.last_opened = self.last_opened.max(other.last_opened);
+
+    for origin in &other.origins {
+      let is_known = self
+        .origins
+        .iter()
+        .any(|known| known.source == origin.source && known.raw_path == origin.raw_path);
+      if !is_known {
+        self.origins.push(origin.clone());
+      }
+    }
+  }
+
+  pub fn working_dir(&self) -> &Path {
+    // Solutions are opened by their file, but the IDE should run from the directory holding it
+    match self.kind.is_file() {
+      true => self.path.parent().unwrap_or(&self.path),
+      false => &self.path,
+    }
+  }
+}
+
+impl ProjectKind {
+  pub fn detect(path: &Path, is_file: bool, is_unity: bool) -> Self {
+    let solution = SolutionFormat::from_path(path);
+
+    match (solution, is_unity) {
+      (_, true) => ProjectKind::Unity(solution),
+      (Some(format), false) => ProjectKind::Solution(format),
+      (None, false) if path.extension().is_some_and(|ext| ext == "ipr") => ProjectKind::Ipr,
+      (None, false) => match BuildFile::from_path(path) {
+        Some(build_file) => ProjectKind::BuildFile(build_file),
+        None if is_file => ProjectKind::File,
+        None => ProjectKind::Directory,
+      },
+    }
+  }
+
+  pub fn is_file(&self) -> bool {
+    !matches!(self, ProjectKind::Directory | ProjectKind::Unity(None))
+  }
+
+  pub fn fallback_name(&self, path: &Path) -> Option<String> {
+    let name = match self {
+      // Build files share the same name across projects, so the directory is more telling
+      ProjectKind::BuildFile(_) => path.parent().and_then(Path::file_name),
+      _ if self.is_file() => path.file_stem(),
+      _ => path.file_name(),
+    };
+
+    name.map(|v| v.to_string_lossy().to_string())
+  }
+
+  pub fn is_solution(&self) -> bool {
+    matches!(self, ProjectKind::Solution(_) | ProjectKind::Unity(Some(_)))
+  }
+}
+
+impl SolutionFormat {
+  fn from_path(path: &Path) -> Option<Self> {
+    let extension = path.extension()?.to_string_lossy().to_lowercase();
+
+    match extension.as_str() {
+      "sln" => Some(SolutionFormat::Sln),
+      "slnx" => Some(SolutionFormat::Slnx),
+      "slnf" => Some(SolutionFormat::Slnf),
+      _ => None,
+    }
+  }
+}
+
+impl ProjectFilter {
+  pub fn matches(&self, project: &RecentProject) -> bool {
+    match self {
+      ProjectFilter::IDE(filter) => filter.matches(&project.ide),
+      ProjectFilter::Group(name) => project
+        .group
+        .as_ref()
+        .is_some_and(|group| group.name.eq_ignore_ascii_case(name)),
+    }
+  }
+}
+
+impl RecentProjectMeta {
+  fn from_map(meta: &HashMap<String, String>) -> Self {
+    let get = |key: &str| meta.get(key).filter(|value| !value.is_empty()).cloned();
+    let get_timestamp = |key: &str| {
+      meta
+        .get(key)
+        .and_then(|raw| raw.parse::<i64>().ok())
+        .filter(|timestamp| *timestamp > 0)
+        .and_then(DateTime::from_timestamp_millis)
+        .map(|dt| dt.with_timezone(&Local))
+    };
+
+    Self {
+      display_name: get("displayName"),
+      frame_title: get("frameTitle"),
+      opened: meta.get("opened").is_some_and(|value| value == "true"),
+      build: get("build"),
+      production_code: get("productionCode"),
+      bin_folder: get("binFolder"),
+      project_workspace_id: get("projectWorkspaceId"),
+      open_timestamp: get_timestamp("projectOpenTimestamp"),
+      activation_timestamp: get_timestamp("activationTimestamp"),
+    }
+  }
+}
+
+impl PathProbe {
+  pub fn run_with_timeout(path: &Path, timeout: Duration) -> Option<Result<Self, String>> {
+    let probed_path = path.to_path_buf();
+    run_with_timeout(path, timeout, move || Self::run(&probed_path))
+  }
+
+  fn run(path: &Path) -> Result<Self, String> {
+    // Validate if project's path exists
+    match path.try_exists() {
+      Ok(false) => {
+        return Ok(Self {
+          missing: true,
+          ..Default::default()
+        });
+      }
+      Err(_) => {
+        return Err(format!(
+          "Ignoring {:?} project, insufficient permissions to access the path",
+          path
+        ));
+      }
+      _ => {}
+    }
+
+    // Projects opened by a file (e.g. a solution or a build file) keep their settings next to it
+    let is_file = path.is_file();
+    let project_dir = match is_file {
+      true => path.parent().unwrap_or(path),
+      false => path,
+    };
+
+    let name = read_to_string(project_dir.join(".idea/.name"))
+      .ok()
+      .map(|raw_name| raw_name.replace('\n', ""));
+
+    // Resolve project's custom icon from project's path
+    let icon = {
+      let glob = ensure_result!(
+        Glob::new(".idea/icon.*").ok(),
+        "Failed to build icon glob matcher for {:?} project",
+        path
+      );
+
+      glob
+        .walk_with_behavior(project_dir, LinkBehavior::ReadTarget)
+        .flatten()
+        .map(WalkEntry::into_path)
+        .next()
+    };
+
+    Ok(Self {
+      missing: false,
+      is_file,
+      is_unity: is_unity_project(project_dir),
+      build_file_name: BuildFile::from_path(path).and_then(|build_file| build_file.read_name(path)),
+      workspace_modified: modified_time(project_dir.join(WORKSPACE_FILE_PATH)),
+      name,
+      icon,
+    })
+  }
+}
+
+pub fn run_with_timeout<T, F>(path: &Path, timeout: Duration, task: F) -> Option<T>
+where
+  T: Send + 'static,
+  F: FnOnce() -> T + Send + 'static,
+{
+  // Other paths on a mount that already timed out would just wait for the same timeout again
+  let mount_point = mount_point(path);
+  if let Some(mount_point) = &mount_point {
+    if lock_stale_mounts().contains(mount_point) {
+      debug!(
+        "Skipping access to {:?}, the {:?} mount is unresponsive",
+        path, mount_point
+      );
+      return None;
+    }
+  }
+
+  let (sender, receiver) = mpsc::channel();
+
+  // A task stuck on an unresponsive mount can't be cancelled, so it's left running detached
+  let spawned = thread::Builder::new()
+    .name(PROBE_THREAD_NAME.to_owned())
+    .spawn(move || {
+      let _ = sender.send(task());
+    })
+    .map_to_error_log(format!("Failed to spawn a thread accessing {:?}", path));
+  if spawned.is_err() {
+    return None;
+  }
+
+  let result = receiver.recv_timeout(timeout).ok();
+
+  // The root mount hosts most of the system, a slow access there doesn't mean it's dead
+  if let Some(mount_point) = mount_point.filter(|_| result.is_none()) {
+    if mount_point != Path::new("/") {
+      lock_stale_mounts().insert(mount_point);
+    }
+  }
+
+  result
+}
+
+pub fn mount_point(path: &Path) -> Option<PathBuf> {
+  // Mount points are read from the kernel, without touching the (possibly unresponsive) filesystem
+  static MOUNT_POINTS: OnceLock<Vec<PathBuf>> = OnceLock::new();
+  let mount_points = MOUNT_POINTS.get_or_init(|| {
+    read_to_string(MOUNT_INFO_PATH)
+      .map_to_error_log(format!("Failed to read {:?} file", MOUNT_INFO_PATH))
+      .unwrap_or_default()
+      .lines()
+      .filter_map(|line| line.split(' ').nth(4))
+      .map(|raw| PathBuf::from(unescape_mount_point(raw)))
+      .collect()
+  });
+
+  mount_points
+    .iter()
+    .filter(|mount_point| path.starts_with(mount_point))
+    .max_by_key(|mount_point| mount_point.as_os_str().len())
+    .cloned()
+}
+
+fn unescape_mount_point(raw: &str) -> String {
+  // Whitespace and backslashes in mount points are escaped as octal sequences (e.g. "\040")
+  let mut result = String::with_capacity(raw.len());
+  let mut rest = raw;
+
+  while let Some(start) = rest.find('\\') {
+    result.push_str(&rest[..start]);
+    let escaped = rest
+      .get(start + 1..start + 4)
+      .and_then(|code| u8::from_str_radix(code, 8).ok());
+
+    match escaped {
+      Some(byte) => {
+        result.push(char::from(byte));
+        rest = &rest[start + 4..];
+      }
+      None => {
+        result.push('\\');
+        rest = &rest[start + 1..];
+      }
+    }
+  }
+
+  result.push_str(rest);
+  result
+}
+
+fn lock_stale_mounts() -> MutexGuard<'static, HashSet<PathBuf>> {
+  static STALE_MOUNTS: OnceLock<Mutex<HashSet<PathBuf>>> = OnceLock::new();
+  STALE_MOUNTS
+    .get_or_init(Default::default)
+    .lock()
+    .unwrap_or_else(PoisonError::into_inner)
+}
+
+pub fn is_unity_project<T: AsRef<Path>>(dir: T) -> bool {
+  UNITY_PROJECT_DIRS
+    .iter()
+    .all(|name| dir.as_ref().join(name).is_dir())
+}
+
+pub fn merge_projects<I>(projects: I) -> Vec<Arc<RecentProject>>
+where
+  I: IntoIterator<Item = Arc<RecentProject>>,
+{
+  let mut merged = Vec::<RecentProject>::new();
+  let mut indices = HashMap::<(PathBuf, String), usize>::new();
+
+  // The same project is listed by several files (e.g. one per installed IDE version), keep all of them
+  for project in projects
+    .into_iter()
+    .sorted_by(|a, b| Ord::cmp(&b.last_opened, &a.last_opened))
+  {
+    let key = (project.path.clone(), project.ide.product_code.clone());
+    match indices.get(&key) {
+      Some(&index) => merged[index].merge(&project),
+      None => {
+        indices.insert(key, merged.len());
+        merged.push(RecentProject::clone(&project));
+      }
+    }
+  }
+
+  // Rider lists a solution in both recent files, once by its file and once by its directory
+  let solutions = merged
+    .iter()
+    .enumerate()
+    .filter(|(_, project)| project.kind.is_solution())
+    .map(|(index, project)| {
+      let key = (
+        project.working_dir().to_path_buf(),
+        project.ide.product_code.clone(),
+      );
+      (key, index)
+    })
+    .into_group_map();
+  let twins = merged
+    .iter()
+    .enumerate()
+    .filter(|(_, project)| !project.kind.is_file())
+    .filter_map(|(index, project)| {
+      let key = (project.path.clone(), project.ide.product_code.clone());
+      Some((index, solutions.get(&key)?.clone()))
+    })
+    .collect::<HashMap<_, _>>();
+
+  for (twin, solutions) in &twins {
+    let twin = merged[*twin].clone();
+    for solution in solutions {
+      merged[*solution].merge(&twin);
+    }
+  }
+
+  merged
+    .into_iter()
+    .enumerate()
+    .filter(|(index, _)| !twins.contains_key(index))
+    .map(|(_, project)| Arc::new(project))
+    .sorted_by(|a, b| Ord::cmp(&b.last_opened, &a.last_opened))
+    .collect()
+}
+
+fn modified_time<T: AsRef<Path>>(path: T) -> Option<SystemTime> {
+  metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+fn element_segment(tag: &str, attributes: &HashMap<String, String>) -> String {
+  match attributes.get("name") {
+    Some(name) => format!("{tag}[@name=\"{name}\"]"),
+    None => tag.to_owned(),
+  }
+}
+
+fn is_manager_component(tag: &str, attributes: &HashMap<String, String>) -> bool {
+  tag == "component"
+    && attributes
+      .get("name")
+      .is_some_and(|name| MANAGER_COMPONENTS.contains(&name.as_str()))
+}
+
+fn is_entry_path(relative_path: &[String]) -> bool {
+  ENTRY_PATHS.contains(&relative_path.join("/").as_str())
+}
+
+fn entry_path_attribute(attributes: &HashMap<String, String>) -> Option<(&'static str, &String)> {
+  ["value", "key"]
+    .into_iter()
+    .find_map(|key| attributes.get(key).map(|value| (key, value)))
+}
+
+fn read_element(element: &BytesStart) -> (String, HashMap<String, String>) {
+  let tag = String::from_utf8_lossy(element.name().as_ref()).to_string();
+  let attributes = element
+    .attributes()
+    .flatten()
+    .filter_map(|attribute| {
+      let key = String::from_utf8_lossy(attribute.key.as_ref()).to_string();
+      let value = attribute.unescape_value().ok()?.to_string();
+
+      Some((key, value))
+    })
+    .collect();
+
+  (tag, attributes)
+}
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use glib::{debug, warn};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use resolve_path::PathResolveExt;
+
+use crate::ide::data::IDEData;
+use crate::recent_project::read_element;
+use crate::G_LOG_DOMAIN;
+
+pub static PATH_MACROS_FILE_PATH: &str = "options/path.macros.xml";
+static PROJECT_DIR_MACRO: &str = "PROJECT_DIR";
+
+#[derive(Debug, Default, Clone)]
+pub struct PathMacros {
+  values: HashMap<String, String>,
+}
+
+impl PathMacros {
+  pub fn load(ide: &IDEData) -> Self {
+    let mut values = HashMap::<String, String>::new();
+
+    // Built-in macros take precedence, as the IDE doesn't allow redefining them
+    values.extend([
+      (
+        "USER_HOME".to_owned(),
+        "~".resolve().to_string_lossy().to_string(),
+      ),
+      (
+        "APPLICATION_HOME_DIR".to_owned(),
+        ide.install_dir.to_string_lossy().to_string(),
+      ),
+      (
+        "APPLICATION_CONFIG_DIR".to_owned(),
+        ide.config_path.to_string_lossy().to_string(),
+      ),
+      (
+        "APPLICATION_PLUGINS_DIR".to_owned(),
+        ide.plugins_path.to_string_lossy().to_string(),
+      ),
+    ]);
+
+    let path = ide.config_path.join(PATH_MACROS_FILE_PATH);
+    for (name, value) in read_user_macros(&path) {
+      values.entry(name).or_insert(value);
+    }
+
+    Self { values }
+  }
+
+  pub fn expand(&self, raw: &str, project_dir: Option<&Path>) -> PathBuf {
+    let mut result = String::with_capacity(raw.len());
+    let mut rest = raw;
+
+    while let Some(start) = rest.find('$') {
+      let Some(end) = rest[start + 1..].find('$').map(|end| start + 1 + end) else {
+        break;
+      };
+
+      let name = &rest[start + 1..end];
+      let value = if name == PROJECT_DIR_MACRO {
+        project_dir.map(|dir| dir.to_string_lossy().to_string())
+      } else {
+        self.values.get(name).cloned()
+      };
+
+      match value {
+        Some(value) => {
+          result.push_str(&rest[..start]);
+          result.push_str(&value);
+          rest = &rest[end + 1..];
+        }
+        None => {
+          // Keep unknown macros as they are, the "$" might be a part of the path
+          result.push_str(&rest[..end]);
+          rest = &rest[end..];
+        }
+      }
+    }
+
+    result.push_str(rest);
+    result.resolve().to_path_buf()
+  }
+}
+
+fn read_user_macros(path: &Path) -> Vec<(String, String)> {
+  let Ok(xml) = read_to_string(path) else {
+    return vec![];
+  };
+  debug!("Reading path macros from {:?} file...", path);
+
+  let mut reader = Reader::from_str(&xml);
+  reader.config_mut().trim_text(true);
+  let mut macros = vec![];
+
+  loop {
+    match reader.read_event() {
+      Ok(Event::Start(element)) | Ok(Event::Empty(element)) => {
+        let (tag, attributes) = read_element(&element);
+
+        if tag == "macro" {
+          if let (Some(name), Some(value)) = (attributes.get("name"), attributes.get("value")) {
+            macros.push((name.clone(), value.clone()));
+          }
+        }
+      }
+      Ok(Event::Eof) => break,
+      Ok(_) => {}
+      Err(err) => {
+        warn!("Failed to parse {:?} file, reason: {}", path, err);
+        break;
+      }
+    }
+  }
+
+  macros
+}